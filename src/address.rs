@@ -0,0 +1,359 @@
+use std::{fmt, str::FromStr};
+
+use custom_error::custom_error;
+
+use crate::types::{BitAddress, ByteAddress, DataType, MemoryArea, RegAddress};
+
+custom_error! {
+/// Error raised when an address cannot be parsed, `column` is the position (starting at `1`)
+/// of the offending character in the address.
+pub AddressError
+    UnexpectedChar{ column: usize, found: char, expected: String } = "Unexpected '{found}' at column {column}, expected {expected}",
+    UnexpectedEnd{ column: usize, expected: String } = "Unexpected end of address at column {column}, expected {expected}",
+    NumberOutOfRange{ column: usize, number: String } = "The number {number} at column {column} is out of range",
+    InvalidBit{ column: usize, bit: String } = "The bit number {bit} at column {column} must be between 0 and 7",
+    UnknownPointerType{ column: usize, name: String } = "Unknown pointer data type {name} at column {column}",
+    MismatchedWidth{ address: String, expected: String } = "The address {address} is not a {expected} address",
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Width of the data designated by an address.
+pub enum AddressWidth {
+    /// A single bit (`DBX`, `I0.0`), holding the bit number.
+    Bit(u8),
+    /// A byte (`DBB`, `MB`).
+    Byte,
+    /// A word of 2 bytes (`DBW`, `MW`).
+    Word,
+    /// A double word of 4 bytes (`DBD`, `MD`).
+    DWord,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Extent of a `P#` pointer (`P#DB1.DBX0.0 BYTE 10` is `10` elements of type `BYTE`).
+pub struct PointerRange {
+    pub data_type: DataType,
+    pub count: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Address parsed from its Siemens notation.
+///
+/// Parameters :
+///
+/// * `area` (`MemoryArea`) - the memory area of the address
+/// * `db` (`u16`) - the number of the data block, `0` outside of data blocks
+/// * `byte` (`u16`) - the offset of the first byte
+/// * `width` (`AddressWidth`) - the width of the data, with the bit number of bit addresses
+/// * `pointer` (`Option<PointerRange>`) - the extent of a `P#` pointer
+pub struct Address {
+    pub area: MemoryArea,
+    pub db: u16,
+    pub byte: u16,
+    pub width: AddressWidth,
+    pub pointer: Option<PointerRange>,
+}
+
+impl Address {
+    /// Size in bytes designated by the address, `None` for a single bit.
+    pub fn size(&self) -> Option<u16> {
+        match self.width {
+            AddressWidth::Bit(_) => None,
+            AddressWidth::Byte => Some(1),
+            AddressWidth::Word => Some(2),
+            AddressWidth::DWord => Some(4),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    /// Formats the address in the English notation accepted by `parse`, the bit addresses of
+    /// pointers and peripheral areas are written with their `X`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_some() {
+            write!(f, "P#")?;
+        }
+        match self.area {
+            MemoryArea::DataBlock => write!(f, "DB{}.DB", self.db)?,
+            area => write!(f, "{area}")?,
+        }
+        let short_bit = self.pointer.is_none()
+            && matches!(
+                self.area,
+                MemoryArea::Input | MemoryArea::Output | MemoryArea::Flag
+            );
+        match self.width {
+            AddressWidth::Bit(bit) if short_bit => write!(f, "{}.{bit}", self.byte)?,
+            AddressWidth::Bit(bit) => write!(f, "X{}.{bit}", self.byte)?,
+            AddressWidth::Byte => write!(f, "B{}", self.byte)?,
+            AddressWidth::Word => write!(f, "W{}", self.byte)?,
+            AddressWidth::DWord => write!(f, "D{}", self.byte)?,
+        }
+        if let Some(pointer) = self.pointer {
+            write!(f, " {} {}", pointer.data_type.name(), pointer.count)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&Address> for RegAddress {
+    fn from(value: &Address) -> Self {
+        match value.width {
+            AddressWidth::Bit(bit) => RegAddress::Bit(BitAddress {
+                area: value.area,
+                db: value.db,
+                byte: value.byte,
+                bit,
+            }),
+            _ => RegAddress::Byte(ByteAddress {
+                area: value.area,
+                db: value.db,
+                byte: value.byte,
+            }),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+/// Area mnemonics, English (IEC) and German (SIMATIC) ones, longest first so that
+/// peripheral areas are matched before inputs and outputs.
+const AREA_MNEMONICS: [(&str, MemoryArea); 9] = [
+    ("PI", MemoryArea::PeripheralInput),
+    ("PE", MemoryArea::PeripheralInput),
+    ("PQ", MemoryArea::PeripheralOutput),
+    ("PA", MemoryArea::PeripheralOutput),
+    ("I", MemoryArea::Input),
+    ("E", MemoryArea::Input),
+    ("Q", MemoryArea::Output),
+    ("A", MemoryArea::Output),
+    ("M", MemoryArea::Flag),
+];
+
+/// Parses an address written in the Siemens notation.
+///
+/// Accepted forms, case insensitive and with an optional TIA `%` prefix :
+/// * data blocks : `DB1.DBX0.0`, `DB1.DBB2`, `DB1.DBW4`, `DB1.DBD8`
+/// * other areas : `I0.0`, `QX4.1`, `QB4`, `MW20`, `MD100`, `PIW256`, `PQB8`, `PIX0.0`
+/// * German mnemonics : `E0.0`, `AW4`, `PEW256`, `PAB8`
+/// * pointers : `P#DB1.DBX0.0 BYTE 10`, `P#M10.0 INT 4`
+///
+/// Errors :
+/// Returns an `AddressError` locating the first character that does not fit the notation.
+pub fn parse(input: &str) -> Result<Address, AddressError> {
+    let mut parser = Parser {
+        chars: input
+            .trim_end()
+            .chars()
+            .map(|c| c.to_ascii_uppercase())
+            .collect(),
+        pos: input.chars().take_while(|c| c.is_whitespace()).count(),
+    };
+    let res = if parser.eat("P#") {
+        let mut addr = parser.location(true)?;
+        parser.spaces()?;
+        let data_type = parser.data_type()?;
+        parser.spaces()?;
+        let count = parser.number()?;
+        addr.pointer = Some(PointerRange { data_type, count });
+        addr
+    } else {
+        parser.eat("%");
+        parser.location(false)?
+    };
+    parser.end()?;
+    Ok(res)
+}
+
+/// Cursor over the characters of an address.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn column(&self) -> usize {
+        self.pos + 1
+    }
+
+    fn error(&self, expected: &str) -> AddressError {
+        match self.peek() {
+            Some(found) => AddressError::UnexpectedChar {
+                column: self.column(),
+                found,
+                expected: expected.to_string(),
+            },
+            None => AddressError::UnexpectedEnd {
+                column: self.column(),
+                expected: expected.to_string(),
+            },
+        }
+    }
+
+    /// Consumes `token` if the remaining input starts with it.
+    fn eat(&mut self, token: &str) -> bool {
+        let len = token.chars().count();
+        let matches = self
+            .chars
+            .get(self.pos..self.pos + len)
+            .is_some_and(|chars| chars.iter().copied().eq(token.chars()));
+        if matches {
+            self.pos += len;
+        }
+        matches
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), AddressError> {
+        if !self.eat(token) {
+            return Err(self.error(&format!("'{token}'")));
+        }
+        Ok(())
+    }
+
+    fn spaces(&mut self) -> Result<(), AddressError> {
+        if self.peek() != Some(' ') {
+            return Err(self.error("a space"));
+        }
+        while self.peek() == Some(' ') {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn end(&self) -> Result<(), AddressError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("the end of the address")),
+        }
+    }
+
+    fn number(&mut self) -> Result<u16, AddressError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("a number"));
+        }
+        let number: String = self.chars[start..self.pos].iter().collect();
+        number.parse().map_err(|_| AddressError::NumberOutOfRange {
+            column: start + 1,
+            number,
+        })
+    }
+
+    fn bit(&mut self) -> Result<u8, AddressError> {
+        let column = self.column();
+        let bit = self.number()?;
+        if bit > 7 {
+            return Err(AddressError::InvalidBit {
+                column,
+                bit: bit.to_string(),
+            });
+        }
+        Ok(bit as u8)
+    }
+
+    fn data_type(&mut self) -> Result<DataType, AddressError> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("a data type"));
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        DataType::from_name(&name).ok_or(AddressError::UnknownPointerType {
+            column: start + 1,
+            name,
+        })
+    }
+
+    /// Parses a `byte.bit` offset.
+    fn bit_offset(&mut self) -> Result<(u16, AddressWidth), AddressError> {
+        let byte = self.number()?;
+        self.expect(".")?;
+        Ok((byte, AddressWidth::Bit(self.bit()?)))
+    }
+
+    /// Parses the width letter and the offset following an area mnemonic.
+    ///
+    /// `bit_only` restricts the address to the `X` form used by pointers and `implicit_bit`
+    /// accepts bit addresses without the `X` letter (`I0.0`).
+    fn offset(
+        &mut self,
+        bit_only: bool,
+        implicit_bit: bool,
+    ) -> Result<(u16, AddressWidth), AddressError> {
+        let width = match self.peek() {
+            Some('X') => None,
+            Some('B') if !bit_only => Some(AddressWidth::Byte),
+            Some('W') if !bit_only => Some(AddressWidth::Word),
+            Some('D') if !bit_only => Some(AddressWidth::DWord),
+            Some(c) if c.is_ascii_digit() && implicit_bit => return self.bit_offset(),
+            _ => {
+                let mut expected = vec!["'X'"];
+                if !bit_only {
+                    expected.extend(["'B'", "'W'", "'D'"]);
+                }
+                if implicit_bit {
+                    expected.push("a byte number");
+                }
+                return Err(self.error(&expected.join(", ")));
+            }
+        };
+        self.pos += 1;
+        match width {
+            Some(width) => Ok((self.number()?, width)),
+            None => self.bit_offset(),
+        }
+    }
+
+    /// Parses a data block or memory area location.
+    fn location(&mut self, bit_only: bool) -> Result<Address, AddressError> {
+        if self.eat("DB") {
+            let db = self.number()?;
+            self.expect(".")?;
+            self.expect("DB")?;
+            let (byte, width) = self.offset(bit_only, false)?;
+            return Ok(Address {
+                area: MemoryArea::DataBlock,
+                db,
+                byte,
+                width,
+                pointer: None,
+            });
+        }
+        let area = AREA_MNEMONICS
+            .iter()
+            .find(|(mnemonic, _)| self.eat(mnemonic))
+            .map(|(_, area)| *area)
+            .ok_or_else(|| self.error("an area (DB, I, Q, M, PI, PQ, E, A, PE, PA)"))?;
+        // peripheral bits have no short form, only `PIX0.0`
+        let peripheral = matches!(
+            area,
+            MemoryArea::PeripheralInput | MemoryArea::PeripheralOutput
+        );
+        let (byte, width) = self.offset(bit_only, !peripheral)?;
+        Ok(Address {
+            area,
+            db: 0,
+            byte,
+            width,
+            pointer: None,
+        })
+    }
+}
@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use chrono::{Datelike, Timelike};
+pub use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+use crate::errors::S7Error;
+use crate::types::{DataType, RegisterValue};
+
+/// Origin of the S7 `DATE` type, values are stored as a number of days since this date.
+const DATE_EPOCH: NaiveDate = match NaiveDate::from_ymd_opt(1990, 1, 1) {
+    Some(date) => date,
+    None => panic!("invalid S7 date epoch"),
+};
+
+/// Resolution in milliseconds of each `S5TIME` time base.
+const S5TIME_BASES: [i64; 4] = [10, 100, 1_000, 10_000];
+
+/// Decodes a two digits BCD byte
+///
+/// Errors :
+/// - `InvalidRegisterValue` if one of the nibbles is not a decimal digit.
+fn from_bcd(byte: u8) -> Result<u32, S7Error> {
+    let (high, low) = (byte >> 4, byte & 0x0F);
+    if high > 9 || low > 9 {
+        return Err(S7Error::InvalidRegisterValue);
+    }
+    Ok((high * 10 + low).into())
+}
+
+/// Encodes a value between `0` and `99` as a two digits BCD byte
+fn to_bcd(val: u32) -> u8 {
+    (((val / 10) << 4) | (val % 10)) as u8
+}
+
+/// Converts the raw bytes read from the PLC into a date or time value.
+///
+/// Parameters :
+/// * `data_type`: one of `DATE`, `TIME`, `TOD`, `DT`, `DTL`, `S5TIME` or `LTIME`.
+/// * `raw`: the bytes read from the PLC.
+///
+/// Errors :
+/// - `MismatchedRegisterLengthError` if the amount of bytes does not match the type.
+/// - `InvalidRegisterValue` if the bytes do not describe a valid date or time (e.g. bad BCD digits),
+///   or if `data_type` is not a date or time type.
+pub fn decode(data_type: DataType, raw: &[u8]) -> Result<RegisterValue, S7Error> {
+    if raw.len() != data_type.size() as usize {
+        return Err(S7Error::MismatchedRegisterLengthError);
+    }
+    let res = match data_type {
+        DataType::DATE => {
+            let days = u16::from_be_bytes([raw[0], raw[1]]);
+            RegisterValue::Date(DATE_EPOCH + TimeDelta::days(days.into()))
+        }
+        DataType::TOD => {
+            let ms = u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]);
+            let time =
+                NaiveTime::from_num_seconds_from_midnight_opt(ms / 1_000, (ms % 1_000) * 1_000_000)
+                    .ok_or(S7Error::InvalidRegisterValue)?;
+            RegisterValue::TimeOfDay(time)
+        }
+        DataType::TIME => {
+            let ms = i32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]);
+            RegisterValue::Duration(TimeDelta::milliseconds(ms.into()))
+        }
+        DataType::LTIME => {
+            let ns = i64::from_be_bytes(raw.try_into().map_err(|_| S7Error::InvalidRegisterValue)?);
+            RegisterValue::Duration(TimeDelta::nanoseconds(ns))
+        }
+        DataType::S5TIME => {
+            let word = u16::from_be_bytes([raw[0], raw[1]]);
+            let base = S5TIME_BASES[usize::from((word >> 12) & 0x3)];
+            let count =
+                from_bcd(((word >> 8) & 0x0F) as u8)? * 100 + from_bcd((word & 0xFF) as u8)?;
+            RegisterValue::Duration(TimeDelta::milliseconds(i64::from(count) * base))
+        }
+        DataType::DT => {
+            let year = from_bcd(raw[0])?;
+            let year = if year >= 90 { 1900 + year } else { 2000 + year };
+            let ms = from_bcd(raw[6])? * 10 + from_bcd(raw[7] >> 4)?;
+            let date = NaiveDate::from_ymd_opt(year as i32, from_bcd(raw[1])?, from_bcd(raw[2])?)
+                .ok_or(S7Error::InvalidRegisterValue)?;
+            let datetime = date
+                .and_hms_milli_opt(from_bcd(raw[3])?, from_bcd(raw[4])?, from_bcd(raw[5])?, ms)
+                .ok_or(S7Error::InvalidRegisterValue)?;
+            RegisterValue::DateTime(datetime)
+        }
+        DataType::DTL => {
+            let year = u16::from_be_bytes([raw[0], raw[1]]);
+            let ns = u32::from_be_bytes([raw[8], raw[9], raw[10], raw[11]]);
+            let date = NaiveDate::from_ymd_opt(year.into(), raw[2].into(), raw[3].into())
+                .ok_or(S7Error::InvalidRegisterValue)?;
+            let datetime = date
+                .and_hms_nano_opt(raw[5].into(), raw[6].into(), raw[7].into(), ns)
+                .ok_or(S7Error::InvalidRegisterValue)?;
+            RegisterValue::DateTime(datetime)
+        }
+        _ => return Err(S7Error::InvalidRegisterValue),
+    };
+    Ok(res)
+}
+
+/// Converts a date or time value into the raw bytes of `data_type`.
+///
+/// `S5TIME` values are stored with the finest time base able to hold them, the
+/// remainder below the resolution of that base is truncated.
+///
+/// Errors :
+/// - `InvalidRegisterValue` if the value does not match the type or cannot be represented
+///   by it (e.g. a `DT` outside of 1990-2089, a negative `S5TIME`).
+pub fn encode(data_type: DataType, value: &RegisterValue) -> Result<Vec<u8>, S7Error> {
+    let res = match (data_type, value) {
+        (DataType::DATE, RegisterValue::Date(date)) => {
+            let days = date.signed_duration_since(DATE_EPOCH).num_days();
+            let days = u16::try_from(days).map_err(|_| S7Error::InvalidRegisterValue)?;
+            Vec::from(days.to_be_bytes())
+        }
+        (DataType::TOD, RegisterValue::TimeOfDay(time)) => {
+            let ms = time.num_seconds_from_midnight() * 1_000 + time.nanosecond() / 1_000_000;
+            Vec::from(ms.to_be_bytes())
+        }
+        (DataType::TIME, RegisterValue::Duration(duration)) => {
+            let ms = i32::try_from(duration.num_milliseconds())
+                .map_err(|_| S7Error::InvalidRegisterValue)?;
+            Vec::from(ms.to_be_bytes())
+        }
+        (DataType::LTIME, RegisterValue::Duration(duration)) => {
+            let ns = duration
+                .num_nanoseconds()
+                .ok_or(S7Error::InvalidRegisterValue)?;
+            Vec::from(ns.to_be_bytes())
+        }
+        (DataType::S5TIME, RegisterValue::Duration(duration)) => {
+            let ms = duration.num_milliseconds();
+            let (base, resolution) = S5TIME_BASES
+                .iter()
+                .enumerate()
+                .find(|(_, resolution)| ms >= 0 && ms / **resolution <= 999)
+                .ok_or(S7Error::InvalidRegisterValue)?;
+            let count = (ms / resolution) as u32;
+            let word = ((base as u16) << 12)
+                | (u16::from(to_bcd(count / 100)) << 8)
+                | u16::from(to_bcd(count % 100));
+            Vec::from(word.to_be_bytes())
+        }
+        (DataType::DT, RegisterValue::DateTime(datetime)) => {
+            if !(1990..=2089).contains(&datetime.year()) {
+                return Err(S7Error::InvalidRegisterValue);
+            }
+            let ms = datetime.nanosecond() / 1_000_000 % 1_000;
+            vec![
+                to_bcd(datetime.year() as u32 % 100),
+                to_bcd(datetime.month()),
+                to_bcd(datetime.day()),
+                to_bcd(datetime.hour()),
+                to_bcd(datetime.minute()),
+                to_bcd(datetime.second()),
+                to_bcd(ms / 10),
+                (to_bcd(ms % 10) << 4) | datetime.weekday().number_from_sunday() as u8,
+            ]
+        }
+        (DataType::DTL, RegisterValue::DateTime(datetime)) => {
+            let year = u16::try_from(datetime.year()).map_err(|_| S7Error::InvalidRegisterValue)?;
+            let mut res = Vec::with_capacity(12);
+            res.extend(year.to_be_bytes());
+            res.extend([
+                datetime.month() as u8,
+                datetime.day() as u8,
+                datetime.weekday().number_from_sunday() as u8,
+                datetime.hour() as u8,
+                datetime.minute() as u8,
+                datetime.second() as u8,
+            ]);
+            res.extend(datetime.nanosecond().to_be_bytes());
+            res
+        }
+        _ => return Err(S7Error::InvalidRegisterValue),
+    };
+    Ok(res)
+}
+
+impl TryFrom<RegisterValue> for Duration {
+    type Error = S7Error;
+
+    /// Converts a `TIME`, `LTIME` or `S5TIME` value, negative durations are rejected.
+    fn try_from(value: RegisterValue) -> Result<Self, Self::Error> {
+        match value {
+            RegisterValue::Duration(val) => val.to_std().map_err(|_| S7Error::InvalidRegisterValue),
+            _ => Err(S7Error::InvalidRegisterValue),
+        }
+    }
+}
+
+impl TryFrom<Duration> for RegisterValue {
+    type Error = S7Error;
+
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        TimeDelta::from_std(value)
+            .map(RegisterValue::Duration)
+            .map_err(|_| S7Error::InvalidRegisterValue)
+    }
+}
+
+impl TryFrom<RegisterValue> for NaiveDate {
+    type Error = S7Error;
+
+    fn try_from(value: RegisterValue) -> Result<Self, Self::Error> {
+        match value {
+            RegisterValue::Date(val) => Ok(val),
+            RegisterValue::DateTime(val) => Ok(val.date()),
+            _ => Err(S7Error::InvalidRegisterValue),
+        }
+    }
+}
+
+impl TryFrom<RegisterValue> for NaiveTime {
+    type Error = S7Error;
+
+    fn try_from(value: RegisterValue) -> Result<Self, Self::Error> {
+        match value {
+            RegisterValue::TimeOfDay(val) => Ok(val),
+            RegisterValue::DateTime(val) => Ok(val.time()),
+            _ => Err(S7Error::InvalidRegisterValue),
+        }
+    }
+}
+
+impl TryFrom<RegisterValue> for NaiveDateTime {
+    type Error = S7Error;
+
+    fn try_from(value: RegisterValue) -> Result<Self, Self::Error> {
+        match value {
+            RegisterValue::DateTime(val) => Ok(val),
+            _ => Err(S7Error::InvalidRegisterValue),
+        }
+    }
+}
+
+/// Serializes a `TimeDelta` as a number of nanoseconds, the resolution of `LTIME`.
+pub mod nanoseconds {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::TimeDelta;
+
+    pub fn serialize<S: Serializer>(value: &TimeDelta, serializer: S) -> Result<S::Ok, S::Error> {
+        match value.num_nanoseconds() {
+            Some(ns) => ns.serialize(serializer),
+            None => Err(serde::ser::Error::custom("duration out of the LTIME range")),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TimeDelta, D::Error> {
+        let ns = i64::deserialize(deserializer)?;
+        Ok(TimeDelta::nanoseconds(ns))
+    }
+}
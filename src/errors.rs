@@ -1,15 +1,23 @@
 use custom_error::custom_error;
 
-
 custom_error! {
 /// This code snippet is defining a custom error type `S7Error` using the `custom_error`
 /// crate in Rust.
 pub S7Error
     S7ClientError {err: s7_client::Error} = "Client error {err}",
     DeviceNotConnectedError = "The device is not connected",
+    UnsupportedAreaError {area: String} = "The memory area {area} cannot be accessed by the client",
     MismatchedRegisterLengthError = "The given register length does not match the selected register",
     RegisterDoesNotExistsError = "The selected register does not exist",
+    AddressOverflowError {name: String} = "The register {name} starts past the 65535 bytes of its memory area",
+    RegisterAlreadyExistsError {name: String} = "The register {name} already exists",
+    GroupDoesNotExistsError {name: String} = "The group {name} does not exist",
     InvalidRegisterValue = "The register value is invalid",
+    StringTooLongError {len: usize, capacity: u16} = "The string length {len} exceeds the register capacity {capacity}",
+    InvalidStringLength {capacity: u16, max: u16} = "The string capacity {capacity} exceeds the maximum {max} of its type",
+    ReadOnlyRegisterError {name: String} = "The register {name} is read-only",
+    WriteOnlyRegisterError {name: String} = "The register {name} is write-only",
+    OutOfLimitsError {name: String, value: f64} = "The value {value} is out of the limits of the register {name}",
 }
 
 impl From<s7_client::Error> for S7Error {
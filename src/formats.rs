@@ -0,0 +1,467 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+
+use custom_error::custom_error;
+use serde::{Deserialize, Serialize};
+
+use crate::address::{Address, AddressWidth};
+use crate::types::{Access, ByteOrder, DataType, RegAddress, Register, Scaling};
+use crate::utils::{build_defs, DefinitionsFormat, FieldFormat, JsonReadError, RegistersFormat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Formats of the register definition files
+pub enum DefinitionFormat {
+    /// A list of registers or a document with `types`, `registers` and `groups`
+    Json,
+    /// One register per row, see `CsvRow` for the columns, struct registers are not supported
+    Csv,
+    /// The same structure as the JSON format
+    Yaml,
+    /// The document form of the JSON format (`[[registers]]` tables)
+    Toml,
+}
+
+impl DefinitionFormat {
+    /// Format of a file from its extension (`json`, `csv`, `yaml`/`yml`, `toml`, case insensitive).
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(DefinitionFormat::Json),
+            "csv" => Some(DefinitionFormat::Csv),
+            "yaml" | "yml" => Some(DefinitionFormat::Yaml),
+            "toml" => Some(DefinitionFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Format of a file from the extension of its path.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_extension(path.extension()?.to_str()?)
+    }
+}
+
+custom_error! {pub FormatError
+    Io{ err: std::io::Error } = "Read/write error : {err}",
+    UnknownFormat{ path: String } = "Unknown definition format of {path}",
+    Json{ err: serde_json::Error } = "Json error : {err}",
+    Csv{ err: csv::Error } = "Csv error : {err}",
+    CsvField{ line: u64, column: String, value: String } = "Invalid {column} '{value}' at line {line}",
+    Yaml{ err: serde_yaml::Error } = "Yaml error : {err}",
+    TomlRead{ err: toml::de::Error } = "Toml parse error : {err}",
+    TomlWrite{ err: toml::ser::Error } = "Toml write error : {err}",
+    Definitions{ err: JsonReadError } = "{err}",
+    Unsupported{ name: String, format: String } = "The register {name} cannot be written in {format}"
+}
+
+impl From<std::io::Error> for FormatError {
+    fn from(value: std::io::Error) -> Self {
+        FormatError::Io { err: value }
+    }
+}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(value: serde_json::Error) -> Self {
+        FormatError::Json { err: value }
+    }
+}
+
+impl From<csv::Error> for FormatError {
+    fn from(value: csv::Error) -> Self {
+        FormatError::Csv { err: value }
+    }
+}
+
+impl From<serde_yaml::Error> for FormatError {
+    fn from(value: serde_yaml::Error) -> Self {
+        FormatError::Yaml { err: value }
+    }
+}
+
+impl From<toml::de::Error> for FormatError {
+    fn from(value: toml::de::Error) -> Self {
+        FormatError::TomlRead { err: value }
+    }
+}
+
+impl From<toml::ser::Error> for FormatError {
+    fn from(value: toml::ser::Error) -> Self {
+        FormatError::TomlWrite { err: value }
+    }
+}
+
+impl From<JsonReadError> for FormatError {
+    fn from(value: JsonReadError) -> Self {
+        FormatError::Definitions { err: value }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+/// Row of a CSV definition file, the first row holds the names of the columns
+///
+/// The columns are the properties of `RegistersFormat`, except :
+/// * `array` - the bounds written `lower..upper` (`0..9`)
+/// * `factor`, `offset` or `raw_min`, `raw_max`, `eng_min`, `eng_max` - the scaling, by factor
+///   and offset or by ranges
+/// * `tags` - the tags separated by `;`
+///
+/// Only `name`, `id` and `type` are required, the other columns can be omitted or left empty.
+struct CsvRow {
+    name: String,
+    id: String,
+    #[serde(rename = "type")]
+    type_: DataType,
+    #[serde(default)]
+    length: Option<u16>,
+    #[serde(default)]
+    array: Option<String>,
+    #[serde(default)]
+    factor: Option<f64>,
+    #[serde(default)]
+    offset: Option<f64>,
+    #[serde(default)]
+    raw_min: Option<f64>,
+    #[serde(default)]
+    raw_max: Option<f64>,
+    #[serde(default)]
+    eng_min: Option<f64>,
+    #[serde(default)]
+    eng_max: Option<f64>,
+    #[serde(default)]
+    unit: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    access: Option<Access>,
+    #[serde(default)]
+    min: Option<f64>,
+    #[serde(default)]
+    max: Option<f64>,
+    #[serde(default)]
+    tags: Option<String>,
+    #[serde(default)]
+    byte_order: Option<ByteOrder>,
+}
+
+impl CsvRow {
+    fn into_format(self, line: u64) -> Result<RegistersFormat, FormatError> {
+        let invalid = |column: &str, value: String| FormatError::CsvField {
+            line,
+            column: column.to_string(),
+            value,
+        };
+        let array = match self.array {
+            None => None,
+            Some(array) => {
+                let bounds = array
+                    .split_once("..")
+                    .and_then(|(lower, upper)| {
+                        Some((lower.trim().parse().ok()?, upper.trim().parse().ok()?))
+                    })
+                    .ok_or_else(|| invalid("array", array.clone()))?;
+                Some(bounds.into())
+            }
+        };
+        let scaling = match (self.raw_min, self.raw_max, self.eng_min, self.eng_max) {
+            (Some(raw_min), Some(raw_max), Some(eng_min), Some(eng_max)) => Some(Scaling::Range {
+                raw: (raw_min, raw_max),
+                eng: (eng_min, eng_max),
+            }),
+            (None, None, None, None) if self.factor.is_none() && self.offset.is_none() => None,
+            (None, None, None, None) => Some(Scaling::Linear {
+                factor: self.factor.unwrap_or(1.0),
+                offset: self.offset.unwrap_or_default(),
+            }),
+            _ => return Err(invalid("scaling", "incomplete range".to_string())),
+        };
+        Ok(RegistersFormat {
+            name: self.name,
+            id: self.id,
+            type_: self.type_,
+            length: self.length,
+            array,
+            struct_: None,
+            scaling,
+            unit: self.unit,
+            description: self.description,
+            access: self.access.unwrap_or_default(),
+            min: self.min,
+            max: self.max,
+            tags: self
+                .tags
+                .iter()
+                .flat_map(|tags| tags.split(';'))
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            byte_order: self.byte_order.unwrap_or_default(),
+        })
+    }
+
+    fn from_format(f: RegistersFormat) -> Self {
+        let (factor, offset, range) = match f.scaling {
+            None => (None, None, None),
+            Some(Scaling::Linear { factor, offset }) => (Some(factor), Some(offset), None),
+            Some(Scaling::Range { raw, eng }) => (None, None, Some((raw, eng))),
+        };
+        CsvRow {
+            name: f.name,
+            id: f.id,
+            type_: f.type_,
+            length: f.length,
+            array: f
+                .array
+                .map(|bounds| format!("{}..{}", bounds.lower, bounds.upper)),
+            factor,
+            offset,
+            raw_min: range.map(|(raw, _)| raw.0),
+            raw_max: range.map(|(raw, _)| raw.1),
+            eng_min: range.map(|(_, eng)| eng.0),
+            eng_max: range.map(|(_, eng)| eng.1),
+            unit: f.unit,
+            description: f.description,
+            access: Some(f.access).filter(|access| !access.is_read_write()),
+            min: f.min,
+            max: f.max,
+            tags: Some(f.tags.join(";")).filter(|tags| !tags.is_empty()),
+            byte_order: Some(f.byte_order).filter(|order| !order.is_big_endian()),
+        }
+    }
+}
+
+/// Reads register definitions in any of the supported formats.
+///
+/// Parameters :
+///
+/// * `input` (`impl Read`) - the source of the definitions
+/// * `format` (`DefinitionFormat`) - the format of the definitions
+///
+/// Returns :
+/// The registers by name, as `utils::get_defs_from_json` for the JSON format.
+///
+/// Errors :
+/// Returns a `FormatError` if the source cannot be read or parsed, or if a register cannot
+/// be built from its definition.
+pub fn read_defs(
+    mut input: impl Read,
+    format: DefinitionFormat,
+) -> Result<HashMap<String, Register>, FormatError> {
+    let defs = match format {
+        DefinitionFormat::Json => DefinitionsFormat::from_value(serde_json::from_reader(input)?)?,
+        DefinitionFormat::Yaml => {
+            let raw: serde_json::Value = serde_yaml::from_reader(input)?;
+            DefinitionsFormat::from_value(raw)?
+        }
+        DefinitionFormat::Toml => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            let raw: serde_json::Value = toml::from_str(&text)?;
+            DefinitionsFormat::from_value(raw)?
+        }
+        DefinitionFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(input);
+            let headers = reader.headers()?.clone();
+            let mut registers = Vec::new();
+            for record in reader.records() {
+                let record = record?;
+                let line = record.position().map_or(0, |position| position.line());
+                let row: CsvRow = record.deserialize(Some(&headers))?;
+                registers.push(row.into_format(line)?);
+            }
+            DefinitionsFormat {
+                types: HashMap::new(),
+                registers,
+                groups: HashMap::new(),
+                instances: Vec::new(),
+            }
+        }
+    };
+    Ok(build_defs(defs)?)
+}
+
+/// Reads a register definition file, whose format is given by its extension.
+///
+/// Errors :
+/// Returns `UnknownFormat` if the extension is not one of a `DefinitionFormat`, otherwise
+/// the errors of `read_defs`.
+pub fn read_defs_from_path(
+    path: impl AsRef<Path>,
+) -> Result<HashMap<String, Register>, FormatError> {
+    let path = path.as_ref();
+    let format = DefinitionFormat::from_path(path).ok_or_else(|| FormatError::UnknownFormat {
+        path: path.display().to_string(),
+    })?;
+    read_defs(File::open(path)?, format)
+}
+
+/// Writes register definitions in any of the supported formats, `read_defs` reads them back
+/// to the same registers.
+///
+/// The registers are written in the order of their names. The struct types of struct registers
+/// are named after their register (`Pump` for the register `Pump`, `Pump.Valve` for its struct
+/// field `Valve`), the groups are kept in the `tags` of the registers and the initial values
+/// are not written.
+///
+/// Parameters :
+///
+/// * `output` (`impl Write`) - the destination of the definitions
+/// * `defs` (`&HashMap<String, Register>`) - the registers to write
+/// * `format` (`DefinitionFormat`) - the format to write
+///
+/// Errors :
+/// Returns a `FormatError` if the definitions cannot be written, or `Unsupported` for a struct
+/// register in the CSV format.
+pub fn write_defs(
+    mut output: impl Write,
+    defs: &HashMap<String, Register>,
+    format: DefinitionFormat,
+) -> Result<(), FormatError> {
+    let mut regs: Vec<&Register> = defs.values().collect();
+    regs.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut types = HashMap::new();
+    let registers: Vec<RegistersFormat> = regs
+        .into_iter()
+        .map(|reg| register_format(reg, &mut types))
+        .collect();
+    let doc = DefinitionsFormat {
+        types,
+        registers,
+        groups: HashMap::new(),
+        instances: Vec::new(),
+    };
+    match format {
+        // the plain list when there is no struct type, as the historical format
+        DefinitionFormat::Json if doc.types.is_empty() => {
+            serde_json::to_writer_pretty(&mut output, &doc.registers)?
+        }
+        DefinitionFormat::Json => serde_json::to_writer_pretty(&mut output, &doc)?,
+        DefinitionFormat::Yaml if doc.types.is_empty() => {
+            serde_yaml::to_writer(&mut output, &doc.registers)?
+        }
+        DefinitionFormat::Yaml => serde_yaml::to_writer(&mut output, &doc)?,
+        DefinitionFormat::Toml => output.write_all(toml::to_string(&doc)?.as_bytes())?,
+        DefinitionFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut output);
+            for f in doc.registers {
+                if f.struct_.is_some() {
+                    return Err(FormatError::Unsupported {
+                        name: f.name,
+                        format: "CSV".to_string(),
+                    });
+                }
+                writer.serialize(CsvRow::from_format(f))?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes register definitions to a file, whose format is given by its extension.
+///
+/// Errors :
+/// Returns `UnknownFormat` if the extension is not one of a `DefinitionFormat`, otherwise
+/// the errors of `write_defs`.
+pub fn write_defs_to_path(
+    path: impl AsRef<Path>,
+    defs: &HashMap<String, Register>,
+) -> Result<(), FormatError> {
+    let path = path.as_ref();
+    let format = DefinitionFormat::from_path(path).ok_or_else(|| FormatError::UnknownFormat {
+        path: path.display().to_string(),
+    })?;
+    write_defs(File::create(path)?, defs, format)
+}
+
+/// Address of a register in the notation of the definition files, with the width of its type
+/// (`DB1.DBD4` for a `REAL`). The strings and structs, whose size depends on their
+/// declaration, are addressed by their first byte (`DB1.DBB0` for a `STRING[2]`).
+pub(crate) fn register_id(reg: &Register) -> String {
+    let (area, db, byte, bit) = match &reg.addr {
+        RegAddress::Bit(addr) => (addr.area, addr.db, addr.byte, Some(addr.bit)),
+        RegAddress::Byte(addr) => (addr.area, addr.db, addr.byte, None),
+    };
+    let variable_size = matches!(
+        reg.data_type,
+        DataType::STRING | DataType::WSTRING | DataType::STRUCT
+    );
+    let width = match (bit, reg.data_type.size()) {
+        (Some(bit), _) => AddressWidth::Bit(bit),
+        (None, 2) if !variable_size => AddressWidth::Word,
+        (None, 4) if !variable_size => AddressWidth::DWord,
+        _ => AddressWidth::Byte,
+    };
+    Address {
+        area,
+        db,
+        byte,
+        width,
+        pointer: None,
+    }
+    .to_string()
+}
+
+/// Converts a register to its definition, adding the struct types of struct registers to `types`.
+fn register_format(
+    reg: &Register,
+    types: &mut HashMap<String, Vec<FieldFormat>>,
+) -> RegistersFormat {
+    RegistersFormat {
+        name: reg.name.clone(),
+        id: register_id(reg),
+        type_: reg.data_type,
+        length: reg.length,
+        array: reg.array,
+        struct_: struct_type(&reg.name, reg, types),
+        scaling: reg.scaling,
+        unit: reg.unit.clone(),
+        description: reg.description.clone(),
+        access: reg.access,
+        min: reg.min,
+        max: reg.max,
+        tags: reg.tags.clone(),
+        byte_order: reg.byte_order,
+    }
+}
+
+/// Declares the struct type `name` of a struct register or field in `types`, `None` if it is
+/// not a struct.
+fn struct_type(
+    name: &str,
+    reg: &Register,
+    types: &mut HashMap<String, Vec<FieldFormat>>,
+) -> Option<String> {
+    if reg.data_type != DataType::STRUCT {
+        return None;
+    }
+    let fields = reg
+        .fields
+        .iter()
+        .map(|field| FieldFormat {
+            name: field.name.clone(),
+            type_: field.data_type,
+            offset: field.byte_offset(),
+            bit: match &field.addr {
+                RegAddress::Bit(addr) if addr.bit > 0 => Some(addr.bit),
+                _ => None,
+            },
+            length: field.length,
+            array: field.array,
+            struct_: struct_type(&format!("{name}.{}", field.name), field, types),
+            scaling: field.scaling,
+            unit: field.unit.clone(),
+            description: field.description.clone(),
+            access: field.access,
+            min: field.min,
+            max: field.max,
+            tags: field.tags.clone(),
+            byte_order: field.byte_order,
+        })
+        .collect();
+    types.insert(name.to_string(), fields);
+    Some(name.to_string())
+}
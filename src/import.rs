@@ -0,0 +1,8 @@
+//! Importers of register definitions from the sources exported by the Siemens engineering tools.
+//!
+//! Every importer produces the same map of registers as `utils::get_defs_from_json`.
+
+pub mod awl;
+pub mod scl;
+pub mod source;
+pub mod xlsx;
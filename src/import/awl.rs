@@ -0,0 +1,36 @@
+use std::{collections::HashMap, fs::File};
+
+use crate::import::source::{self, SourceError};
+use crate::types::Register;
+use crate::utils::read_text;
+
+/// Imports the registers declared by the data blocks of a STEP 7 AWL (STL) source.
+///
+/// AWL data blocks (`DATA_BLOCK DB 10`, `TYPE UDT 1`, `STRUCT ... END_STRUCT`, `BEGIN`) are
+/// declared with the same syntax as the SCL ones, the registers are named `DB10.Member` and
+/// laid out with the S7-300 alignment rules, see `scl::get_defs_from_scl` for the details.
+///
+/// Parameters :
+///
+/// * `input` (`File`) - the AWL source, in UTF-8 or in the Windows code page of STEP 7
+/// * `numbers` (`&HashMap<String, u16>`) - the numbers of the data blocks declared with a
+///   symbolic name (`DATA_BLOCK "Recipe"`)
+///
+/// Errors :
+/// Returns a `SourceError` if the source cannot be parsed, uses an unknown type, a
+/// multi-dimensional array or an invalid initial value, declares a data block without number
+/// or two registers of the same name.
+pub fn get_defs_from_awl(
+    mut input: File,
+    numbers: &HashMap<String, u16>,
+) -> Result<HashMap<String, Register>, SourceError> {
+    get_defs_from_awl_str(&read_text(&mut input)?, numbers)
+}
+
+/// Same as `get_defs_from_awl`, from the text of the source.
+pub fn get_defs_from_awl_str(
+    source: &str,
+    numbers: &HashMap<String, u16>,
+) -> Result<HashMap<String, Register>, SourceError> {
+    source::get_defs(source, numbers)
+}
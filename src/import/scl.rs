@@ -0,0 +1,40 @@
+use std::{collections::HashMap, fs::File};
+
+use crate::import::source::{self, SourceError};
+use crate::types::Register;
+use crate::utils::read_text;
+
+/// Imports the registers declared by the data blocks of a TIA Portal source (`.db`, `.scl`).
+///
+/// The members of the `STRUCT` of each `DATA_BLOCK` become registers named `Block.Member`,
+/// at the offsets of a non-optimized block. Nested structs and `"UDT"` instances (declared by
+/// `TYPE` blocks of the same source) become `STRUCT` registers, one-dimensional arrays become
+/// array registers. The comment following a member becomes its description.
+///
+/// The initial values declared with the members and assigned by the `BEGIN` section become
+/// the `initial` value of the registers they belong to.
+///
+/// Parameters :
+///
+/// * `input` (`File`) - the source exported by TIA Portal
+/// * `numbers` (`&HashMap<String, u16>`) - the numbers of the data blocks by name, as the
+///   source does not hold them. Blocks named `DB<n>` do not need an entry.
+///
+/// Errors :
+/// Returns a `SourceError` if the source cannot be parsed, uses an unknown type, a
+/// multi-dimensional array or an invalid initial value, declares an optimized data block, a
+/// data block without number or two registers of the same name.
+pub fn get_defs_from_scl(
+    mut input: File,
+    numbers: &HashMap<String, u16>,
+) -> Result<HashMap<String, Register>, SourceError> {
+    get_defs_from_scl_str(&read_text(&mut input)?, numbers)
+}
+
+/// Same as `get_defs_from_scl`, from the text of the source.
+pub fn get_defs_from_scl_str(
+    source: &str,
+    numbers: &HashMap<String, u16>,
+) -> Result<HashMap<String, Register>, SourceError> {
+    source::get_defs(source, numbers)
+}
@@ -0,0 +1,963 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    io,
+};
+
+use custom_error::custom_error;
+
+use crate::datetime::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use crate::layout::{self, LayoutError};
+use crate::namespace::SEPARATOR;
+use crate::types::{
+    from_f64, ArrayBounds, ByteAddress, DataType, MemoryArea, RegAddress, Register, RegisterValue,
+};
+
+custom_error! {
+/// Error raised when a data block source (SCL of TIA Portal or AWL of STEP 7) cannot be
+/// imported, `line` is the line (starting at `1`) of the offending declaration.
+pub SourceError
+    Io{ err: io::Error } = "Read error : {err}",
+    Syntax{ line: usize, found: String, expected: String } = "Unexpected {found} at line {line}, expected {expected}",
+    UnknownType{ line: usize, name: String } = "Unknown data type {name} at line {line}",
+    RecursiveType{ name: String } = "The type {name} contains itself",
+    InvalidBounds{ line: usize, lower: i32, upper: i32 } = "Invalid array bounds {lower}..{upper} at line {line}",
+    NestedArray{ line: usize } = "Arrays of arrays are not supported (line {line})",
+    MultiDimArray{ line: usize } = "Multi-dimensional arrays are not supported (line {line})",
+    OptimizedBlock{ name: String } = "The data block {name} uses optimized access, its members have no offset",
+    UnknownDbNumber{ name: String } = "The number of the data block {name} is not known",
+    InvalidValue{ line: usize, value: String } = "Invalid initial value {value} at line {line}",
+    UnknownMember{ line: usize, path: String } = "Unknown member {path} at line {line}",
+    UndecodableValue{ name: String } = "The initial value of the member {name} cannot be decoded",
+    DuplicateRegister{ name: String } = "The register {name} is defined several times",
+    Layout{ err: LayoutError } = "{err}",
+}
+
+impl From<io::Error> for SourceError {
+    fn from(value: io::Error) -> Self {
+        SourceError::Io { err: value }
+    }
+}
+
+impl From<LayoutError> for SourceError {
+    fn from(value: LayoutError) -> Self {
+        SourceError::Layout { err: value }
+    }
+}
+
+/// Builds the registers declared by the data blocks of a source, see `scl::get_defs_from_scl`
+/// and `awl::get_defs_from_awl` for the rules.
+pub(crate) fn get_defs(
+    source: &str,
+    numbers: &HashMap<String, u16>,
+) -> Result<HashMap<String, Register>, SourceError> {
+    let mut parser = Parser {
+        tokens: tokenize(source),
+        pos: 0,
+    };
+    let (types, blocks) = parser.source()?;
+    let mut m = HashMap::<String, Register>::new();
+    for block in blocks {
+        if block.optimized {
+            return Err(SourceError::OptimizedBlock { name: block.name });
+        }
+        let db = db_number(&block.name, numbers)?;
+        let mut regs = resolve(&block.body, block.line, &types, &mut Vec::new())?.fields;
+        layout::layout(&mut regs)?;
+        layout::set_db(&mut regs, db);
+        initial_values(&block, &mut regs, &types)?;
+        for mut reg in regs {
+            reg.name = format!("{}{SEPARATOR}{}", block.name, reg.name);
+            match m.entry(reg.name.clone()) {
+                Entry::Occupied(_) => {
+                    return Err(SourceError::DuplicateRegister { name: reg.name })
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(reg);
+                }
+            }
+        }
+    }
+    Ok(m)
+}
+
+fn db_number(name: &str, numbers: &HashMap<String, u16>) -> Result<u16, SourceError> {
+    if let Some(number) = numbers.get(name) {
+        return Ok(*number);
+    }
+    name.get(..2)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("DB"))
+        .and_then(|_| name[2..].parse().ok())
+        .ok_or_else(|| SourceError::UnknownDbNumber {
+            name: name.to_string(),
+        })
+}
+
+/// Computes the `initial` values of the registers of a data block from the values declared
+/// with the members, overwritten by the assignments of the `BEGIN` section.
+///
+/// The values are written into an image of the block, whose bytes are then decoded for the
+/// registers having at least one initial value.
+fn initial_values(
+    block: &DataBlock,
+    regs: &mut [Register],
+    types: &Types,
+) -> Result<(), SourceError> {
+    let size = regs
+        .iter()
+        .map(|reg| usize::from(reg.byte_offset()) + usize::from(reg.size()))
+        .max()
+        .unwrap_or_default();
+    let mut image = vec![0; size];
+    let mut initialized = HashSet::new();
+    let members = match &block.body {
+        Decl::Udt(name) => &types[name],
+        Decl::Struct(members) => members,
+        _ => unreachable!("the body of a data block is a struct"),
+    };
+    for (member, reg) in members.iter().zip(regs.iter()) {
+        if declared_values(member, reg, types, &mut image)? {
+            initialized.insert(reg.name.clone());
+        }
+    }
+    for assignment in &block.assignments {
+        let unknown = || SourceError::UnknownMember {
+            line: assignment.line,
+            path: assignment.path.clone(),
+        };
+        let (first, steps) = assignment.steps.split_first().ok_or_else(unknown)?;
+        let Step::Field(name) = first else {
+            return Err(unknown());
+        };
+        let mut reg = regs
+            .iter()
+            .find(|reg| &reg.name == name)
+            .cloned()
+            .ok_or_else(unknown)?;
+        for step in steps {
+            reg = match step {
+                Step::Field(name) => reg.field(name).ok(),
+                Step::Index(index) => reg.element(*index).ok(),
+            }
+            .ok_or_else(unknown)?;
+        }
+        write_value(&mut image, &reg, &assignment.value, assignment.line)?;
+        initialized.insert(name.clone());
+    }
+    for reg in regs.iter_mut() {
+        if initialized.contains(&reg.name) {
+            let start = reg.byte_offset() as usize;
+            let raw = image[start..start + reg.size() as usize].to_vec();
+            let initial = RegisterValue::try_from((raw, reg.clone())).map_err(|_| {
+                SourceError::UndecodableValue {
+                    name: format!("{}{SEPARATOR}{}", block.name, reg.name),
+                }
+            })?;
+            reg.initial = Some(initial);
+        }
+    }
+    Ok(())
+}
+
+/// Writes the values declared with a member (and with the members of its structs) into the
+/// image of the block, `reg` being the register of the member. Returns whether a value was
+/// written.
+fn declared_values(
+    member: &Member,
+    reg: &Register,
+    types: &Types,
+    image: &mut [u8],
+) -> Result<bool, SourceError> {
+    let instances: Vec<Register> = match reg.array {
+        Some(bounds) => (bounds.lower..=bounds.upper)
+            .filter_map(|index| reg.element(index).ok())
+            .collect(),
+        None => vec![reg.clone()],
+    };
+    if member.values.len() > instances.len() {
+        return Err(SourceError::InvalidValue {
+            line: member.line,
+            value: member.values.join(", "),
+        });
+    }
+    let mut written = !member.values.is_empty();
+    for (instance, value) in instances.iter().zip(&member.values) {
+        write_value(image, instance, value, member.line)?;
+    }
+    let element: &Decl = match &member.decl {
+        Decl::Array { element, .. } => element,
+        decl => decl,
+    };
+    let fields = match element {
+        Decl::Struct(fields) => fields,
+        Decl::Udt(name) => &types[name],
+        _ => return Ok(written),
+    };
+    for instance in &instances {
+        for field in fields {
+            if let Ok(field_reg) = instance.field(&field.name) {
+                written |= declared_values(field, &field_reg, types, image)?;
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Writes the value `text` of the register `reg` into the image of its block.
+fn write_value(
+    image: &mut [u8],
+    reg: &Register,
+    text: &str,
+    line: usize,
+) -> Result<(), SourceError> {
+    let invalid = || SourceError::InvalidValue {
+        line,
+        value: text.to_string(),
+    };
+    let value = parse_value(text, reg.data_type).ok_or_else(invalid)?;
+    let start = reg.byte_offset() as usize;
+    let slice = &mut image[start..start + reg.size() as usize];
+    let merged = reg.merge(value, slice.to_vec()).map_err(|_| invalid())?;
+    slice.copy_from_slice(&merged);
+    Ok(())
+}
+
+/// Converts the text of a literal (`TRUE`, `-5`, `16#FF`, `W#16#FF`, `1.5e+002`, `'text'`,
+/// `T#1H2M`, `S5T#10S`, `D#2024-01-31`, `TOD#12:00:00`, `DT#2024-01-31-12:00:00`) to a value
+/// of the type `data_type`.
+fn parse_value(text: &str, data_type: DataType) -> Option<RegisterValue> {
+    let quoted = text
+        .find('\'')
+        .and_then(|start| text[start + 1..].strip_suffix('\''))
+        .map(|text| text.replace("$'", "'").replace("$$", "$"));
+    // the prefix of typed literals (`INT#5`, `T#1S`) is not checked
+    let untyped = text.split_once('#').map_or(text, |(_, rest)| rest);
+    let res = match data_type {
+        DataType::BOOL => match text.rsplit('#').next()?.to_ascii_uppercase().as_str() {
+            "TRUE" | "1" => RegisterValue::Boolean(true),
+            "FALSE" | "0" => RegisterValue::Boolean(false),
+            _ => return None,
+        },
+        DataType::CHAR => {
+            let chars: Vec<char> = quoted?.chars().collect();
+            match chars[..] {
+                [c] => RegisterValue::Char(c),
+                _ => return None,
+            }
+        }
+        DataType::STRING | DataType::WSTRING => RegisterValue::String(quoted?),
+        DataType::TIME | DataType::LTIME | DataType::S5TIME => {
+            RegisterValue::Duration(parse_duration(untyped)?)
+        }
+        DataType::DATE => RegisterValue::Date(NaiveDate::parse_from_str(untyped, "%Y-%m-%d").ok()?),
+        DataType::TOD => {
+            RegisterValue::TimeOfDay(NaiveTime::parse_from_str(untyped, "%H:%M:%S%.f").ok()?)
+        }
+        DataType::DT | DataType::DTL => RegisterValue::DateTime(
+            NaiveDateTime::parse_from_str(untyped, "%Y-%m-%d-%H:%M:%S%.f").ok()?,
+        ),
+        DataType::STRUCT => return None,
+        _ => {
+            let parts: Vec<&str> = text.split('#').collect();
+            let val = match parts[..] {
+                [.., radix @ ("2" | "8" | "16"), digits] => {
+                    i128::from_str_radix(&digits.replace('_', ""), radix.parse().ok()?).ok()? as f64
+                }
+                [.., digits] => digits.replace('_', "").parse().ok()?,
+                [] => return None,
+            };
+            from_f64(data_type, val).ok()?
+        }
+    };
+    Some(res)
+}
+
+/// Parses an IEC duration (`1D2H3M4S5MS`, `-1.5s`, `1h_30m`), without its `T#` prefix.
+fn parse_duration(text: &str) -> Option<TimeDelta> {
+    let text = text.to_ascii_lowercase().replace('_', "");
+    let (negative, mut rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = TimeDelta::zero();
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+        let number: f64 = rest[..end].parse().ok()?;
+        rest = &rest[end..];
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let nanoseconds = match &rest[..unit_end] {
+            "d" => 86_400e9,
+            "h" => 3_600e9,
+            "m" => 60e9,
+            "s" => 1e9,
+            "ms" => 1e6,
+            "us" => 1e3,
+            "ns" => 1.0,
+            _ => return None,
+        };
+        total += TimeDelta::nanoseconds((number * nanoseconds).round() as i64);
+        rest = &rest[unit_end..];
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Declaration of the type of a member.
+enum Decl {
+    Elementary {
+        data_type: DataType,
+        length: Option<u16>,
+    },
+    Struct(Vec<Member>),
+    Array {
+        bounds: ArrayBounds,
+        element: Box<Decl>,
+    },
+    /// Instance of a user defined type, by name.
+    Udt(String),
+}
+
+struct Member {
+    name: String,
+    decl: Decl,
+    /// Texts of the initial values, one per element for arrays.
+    values: Vec<String>,
+    description: Option<String>,
+    line: usize,
+}
+
+/// Members of the user defined types, by name.
+type Types = HashMap<String, Vec<Member>>;
+
+struct DataBlock {
+    name: String,
+    body: Decl,
+    optimized: bool,
+    assignments: Vec<Assignment>,
+    line: usize,
+}
+
+/// Step of the path of a member in an assignment.
+enum Step {
+    Field(String),
+    Index(i32),
+}
+
+/// Assignment of the `BEGIN` section of a data block (`Motors[1].Speed := 5;`).
+struct Assignment {
+    path: String,
+    steps: Vec<Step>,
+    value: String,
+    line: usize,
+}
+
+/// Builds the register of a declaration, with a placeholder address (see `layout::layout`).
+fn resolve(
+    decl: &Decl,
+    line: usize,
+    types: &Types,
+    stack: &mut Vec<String>,
+) -> Result<Register, SourceError> {
+    let placeholder = RegAddress::Byte(ByteAddress {
+        area: MemoryArea::DataBlock,
+        db: 0,
+        byte: 0,
+    });
+    let reg = match decl {
+        Decl::Elementary { data_type, length } => {
+            let mut reg = Register::new("", *data_type, placeholder);
+            reg.length = *length;
+            reg
+        }
+        Decl::Struct(members) => {
+            let mut reg = Register::new("", DataType::STRUCT, placeholder);
+            reg.fields = members
+                .iter()
+                .map(|member| resolve_member(member, types, stack))
+                .collect::<Result<_, _>>()?;
+            reg
+        }
+        Decl::Array { bounds, element } => {
+            let mut reg = resolve(element, line, types, stack)?;
+            reg.array = Some(*bounds);
+            reg
+        }
+        Decl::Udt(name) => {
+            let members = types.get(name).ok_or_else(|| SourceError::UnknownType {
+                line,
+                name: format!("\"{name}\""),
+            })?;
+            if stack.contains(name) {
+                return Err(SourceError::RecursiveType { name: name.clone() });
+            }
+            stack.push(name.clone());
+            let mut reg = Register::new("", DataType::STRUCT, placeholder);
+            reg.fields = members
+                .iter()
+                .map(|member| resolve_member(member, types, stack))
+                .collect::<Result<_, _>>()?;
+            stack.pop();
+            reg
+        }
+    };
+    Ok(reg)
+}
+
+fn resolve_member(
+    member: &Member,
+    types: &Types,
+    stack: &mut Vec<String>,
+) -> Result<Register, SourceError> {
+    let mut reg = resolve(&member.decl, member.line, types, stack)?;
+    reg.name = member.name.clone();
+    reg.description = member.description.clone();
+    Ok(reg)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifier or keyword.
+    Ident(String),
+    /// Name between double quotes, without the quotes.
+    Quoted(String),
+    /// Text between single quotes, without the quotes.
+    Text(String),
+    Number(String),
+    Symbol(String),
+    Comment(String),
+}
+
+struct Lexeme {
+    token: Token,
+    line: usize,
+}
+
+fn tokenize(source: &str) -> Vec<Lexeme> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut pos, mut line) = (0, 1);
+    // consumes characters up to (not including) the first one matching `end`
+    let take_until = |pos: &mut usize, end: &dyn Fn(usize) -> bool| {
+        let start = *pos;
+        while *pos < chars.len() && !end(*pos) {
+            *pos += 1;
+        }
+        chars[start..*pos].iter().collect::<String>()
+    };
+    while pos < chars.len() {
+        let c = chars[pos];
+        let next = chars.get(pos + 1).copied();
+        let start_line = line;
+        let token = match c {
+            '\n' => {
+                line += 1;
+                pos += 1;
+                continue;
+            }
+            c if c.is_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            '/' if next == Some('/') => {
+                pos += 2;
+                Token::Comment(
+                    take_until(&mut pos, &|i| chars[i] == '\n')
+                        .trim()
+                        .to_string(),
+                )
+            }
+            '(' if next == Some('*') => {
+                pos += 2;
+                let text = take_until(&mut pos, &|i| {
+                    chars[i] == '*' && chars.get(i + 1) == Some(&')')
+                });
+                pos += 2;
+                line += text.matches('\n').count();
+                Token::Comment(text.trim().to_string())
+            }
+            '"' | '\'' => {
+                pos += 1;
+                // `$'` is a quote inside a text
+                let text = take_until(&mut pos, &|i| {
+                    (chars[i] == c && !(c == '\'' && chars[i - 1] == '$')) || chars[i] == '\n'
+                });
+                if chars.get(pos) == Some(&c) {
+                    pos += 1;
+                }
+                match c {
+                    '"' => Token::Quoted(text),
+                    _ => Token::Text(text),
+                }
+            }
+            c if c.is_ascii_digit() => Token::Number(take_until(&mut pos, &|i| {
+                let c = chars[i];
+                let decimal = c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+                !(c.is_ascii_alphanumeric() || c == '_' || c == '#' || decimal)
+            })),
+            c if c.is_alphabetic() || c == '_' => Token::Ident(take_until(&mut pos, &|i| {
+                !(chars[i].is_alphanumeric() || chars[i] == '_')
+            })),
+            ':' if next == Some('=') => {
+                pos += 2;
+                Token::Symbol(":=".to_string())
+            }
+            '.' if next == Some('.') => {
+                pos += 2;
+                Token::Symbol("..".to_string())
+            }
+            c => {
+                pos += 1;
+                Token::Symbol(c.to_string())
+            }
+        };
+        tokens.push(Lexeme {
+            token,
+            line: start_line,
+        });
+    }
+    tokens
+}
+
+/// Cursor over the tokens of a source, the comments are only looked at after declarations.
+struct Parser {
+    tokens: Vec<Lexeme>,
+    pos: usize,
+}
+
+impl Parser {
+    fn skip_comments(&mut self) {
+        while self
+            .tokens
+            .get(self.pos)
+            .is_some_and(|lexeme| matches!(lexeme.token, Token::Comment(_)))
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<&Token> {
+        self.skip_comments();
+        self.tokens.get(self.pos).map(|lexeme| &lexeme.token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        self.skip_comments();
+        let token = self.tokens.get(self.pos).map(|lexeme| lexeme.token.clone());
+        self.pos += 1;
+        token
+    }
+
+    /// Line of the next token.
+    fn line(&mut self) -> usize {
+        self.skip_comments();
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |lexeme| lexeme.line)
+    }
+
+    fn error(&mut self, expected: &str) -> SourceError {
+        let line = self.line();
+        let found = match self.peek() {
+            None => "end of source".to_string(),
+            Some(Token::Ident(s) | Token::Number(s) | Token::Symbol(s)) => format!("'{s}'"),
+            Some(Token::Quoted(s)) => format!("'\"{s}\"'"),
+            Some(Token::Text(s)) => format!("''{s}''"),
+            Some(Token::Comment(_)) => unreachable!(),
+        };
+        SourceError::Syntax {
+            line,
+            found,
+            expected: expected.to_string(),
+        }
+    }
+
+    fn is_keyword(&mut self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SourceError> {
+        if !self.eat_keyword(keyword) {
+            return Err(self.error(keyword));
+        }
+        Ok(())
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(s)) if s == symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), SourceError> {
+        if !self.eat_symbol(symbol) {
+            return Err(self.error(&format!("'{symbol}'")));
+        }
+        Ok(())
+    }
+
+    /// Name of a block or a member, plain or between double quotes. The absolute names of the
+    /// blocks (`DB 10`, `UDT 1`, `FB 1`) are joined (`DB10`).
+    fn name(&mut self) -> Result<String, SourceError> {
+        let name = match self.peek() {
+            Some(Token::Ident(name) | Token::Quoted(name)) => name.clone(),
+            _ => return Err(self.error("a name")),
+        };
+        self.pos += 1;
+        let block = ["DB", "UDT", "FB"]
+            .iter()
+            .any(|block| block.eq_ignore_ascii_case(&name));
+        if let (true, Some(Token::Number(number))) = (block, self.peek()) {
+            let name = format!("{}{number}", name.to_ascii_uppercase());
+            self.pos += 1;
+            return Ok(name);
+        }
+        Ok(name)
+    }
+
+    fn integer(&mut self) -> Result<i32, SourceError> {
+        let negative = self.eat_symbol("-");
+        let number = match self.peek() {
+            Some(Token::Number(number)) => number.parse::<i32>().ok(),
+            _ => None,
+        };
+        let number = number.ok_or_else(|| self.error("an integer"))?;
+        self.pos += 1;
+        Ok(if negative { -number } else { number })
+    }
+
+    /// Parses an optional `{ Name := 'value'; ... }` attribute list.
+    fn attributes(&mut self) -> Result<HashMap<String, String>, SourceError> {
+        let mut attributes = HashMap::new();
+        if !self.eat_symbol("{") {
+            return Ok(attributes);
+        }
+        while !self.eat_symbol("}") {
+            let name = self.name()?;
+            self.expect_symbol(":=")?;
+            let value = match self.next() {
+                Some(Token::Text(value)) => value,
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("a quoted value"));
+                }
+            };
+            attributes.insert(name.to_ascii_uppercase(), value);
+            self.eat_symbol(";");
+        }
+        Ok(attributes)
+    }
+
+    /// Parses the initial values of a declaration, optionally between brackets (TIA Portal).
+    fn values(&mut self) -> Result<Vec<String>, SourceError> {
+        let bracketed = self.eat_symbol("[");
+        let values = self.value_list()?;
+        if bracketed {
+            self.expect_symbol("]")?;
+        }
+        Ok(values)
+    }
+
+    /// Parses a list of values separated by `,`, where `3 (0)` repeats values.
+    fn value_list(&mut self) -> Result<Vec<String>, SourceError> {
+        let mut values = Vec::new();
+        loop {
+            let start = self.pos;
+            let count = match self.next() {
+                Some(Token::Number(count)) => count.parse::<usize>().ok(),
+                _ => None,
+            };
+            match count {
+                Some(count) if self.eat_symbol("(") => {
+                    let repeated = self.value_list()?;
+                    self.expect_symbol(")")?;
+                    for _ in 0..count {
+                        values.extend(repeated.iter().cloned());
+                    }
+                }
+                _ => {
+                    self.pos = start;
+                    values.push(self.literal()?);
+                }
+            }
+            if !self.eat_symbol(",") {
+                return Ok(values);
+            }
+        }
+    }
+
+    /// Reads the text of a literal (`-5`, `16#FF`, `T#1S`, `'text'`...) up to the next `,`,
+    /// `;`, `)` or `]`.
+    fn literal(&mut self) -> Result<String, SourceError> {
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None => break,
+                Some(Token::Symbol(s)) if [",", ";", ")", "]"].contains(&s.as_str()) => break,
+                Some(Token::Text(s)) => text += &format!("'{s}'"),
+                Some(Token::Quoted(s)) => text += &format!("\"{s}\""),
+                Some(Token::Ident(s) | Token::Number(s) | Token::Symbol(s)) => text += s,
+                Some(Token::Comment(_)) => unreachable!(),
+            }
+            self.pos += 1;
+        }
+        if text.is_empty() {
+            return Err(self.error("a value"));
+        }
+        Ok(text)
+    }
+
+    /// Parses an assignment of the `BEGIN` section (`Motors[1].Speed := 5;`).
+    fn assignment(&mut self) -> Result<Assignment, SourceError> {
+        let line = self.line();
+        let name = self.name()?;
+        let mut path = name.clone();
+        let mut steps = vec![Step::Field(name)];
+        loop {
+            if self.eat_symbol(".") {
+                let name = self.name()?;
+                path += &format!("{SEPARATOR}{name}");
+                steps.push(Step::Field(name));
+            } else if self.eat_symbol("[") {
+                let index = self.integer()?;
+                self.expect_symbol("]")?;
+                path += &format!("[{index}]");
+                steps.push(Step::Index(index));
+            } else {
+                break;
+            }
+        }
+        self.expect_symbol(":=")?;
+        let value = self.literal()?;
+        self.expect_symbol(";")?;
+        Ok(Assignment {
+            path,
+            steps,
+            value,
+            line,
+        })
+    }
+
+    /// Comment on the same line as the previous token, e.g. after the `;` of a declaration.
+    fn trailing_comment(&mut self) -> Option<String> {
+        let line = self.tokens.get(self.pos.checked_sub(1)?)?.line;
+        match self.tokens.get(self.pos) {
+            Some(Lexeme {
+                token: Token::Comment(text),
+                line: comment_line,
+            }) if *comment_line == line && !text.is_empty() => {
+                let text = text.clone();
+                self.pos += 1;
+                Some(text)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses the type blocks and the data blocks of the source, other blocks are skipped.
+    fn source(&mut self) -> Result<(Types, Vec<DataBlock>), SourceError> {
+        let mut types = HashMap::new();
+        let mut blocks = Vec::new();
+        while self.peek().is_some() {
+            if self.eat_keyword("TYPE") {
+                let name = self.name()?;
+                while !self.is_keyword("STRUCT") {
+                    self.header_item()?;
+                }
+                let members = self.members()?;
+                self.eat_symbol(";");
+                self.expect_keyword("END_TYPE")?;
+                types.insert(name, members);
+            } else if self.eat_keyword("DATA_BLOCK") {
+                blocks.push(self.data_block()?);
+            } else {
+                self.pos += 1;
+            }
+        }
+        Ok((types, blocks))
+    }
+
+    /// Skips an item of the header of a block (`VERSION : 0.1`, `NON_RETAIN`, attributes...).
+    fn header_item(&mut self) -> Result<(), SourceError> {
+        match self.peek() {
+            None => Err(self.error("STRUCT")),
+            Some(Token::Symbol(s)) if s == "{" => self.attributes().map(|_| ()),
+            _ => {
+                self.pos += 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn data_block(&mut self) -> Result<DataBlock, SourceError> {
+        let line = self.line();
+        let name = self.name()?;
+        let mut optimized = false;
+        let body = loop {
+            if self.is_keyword("STRUCT") {
+                break Decl::Struct(self.members()?);
+            }
+            if self.is_keyword("BEGIN") || self.is_keyword("END_DATA_BLOCK") {
+                return Err(self.error("STRUCT or a type name"));
+            }
+            match self.peek() {
+                Some(Token::Symbol(s)) if s == "{" => {
+                    let attributes = self.attributes()?;
+                    optimized |= attributes
+                        .get("S7_OPTIMIZED_ACCESS")
+                        .is_some_and(|value| value.eq_ignore_ascii_case("TRUE"));
+                }
+                Some(Token::Quoted(_)) => break Decl::Udt(self.name()?),
+                Some(Token::Ident(s))
+                    if s.eq_ignore_ascii_case("UDT") || s.eq_ignore_ascii_case("FB") =>
+                {
+                    break Decl::Udt(self.name()?)
+                }
+                _ => self.header_item()?,
+            }
+        };
+        let mut assignments = Vec::new();
+        while !self.eat_keyword("END_DATA_BLOCK") {
+            if self.eat_keyword("BEGIN") {
+                while !self.is_keyword("END_DATA_BLOCK") {
+                    assignments.push(self.assignment()?);
+                }
+            } else if self.next().is_none() {
+                return Err(self.error("END_DATA_BLOCK"));
+            }
+        }
+        Ok(DataBlock {
+            name,
+            body,
+            optimized,
+            assignments,
+            line,
+        })
+    }
+
+    /// Parses `STRUCT <members> END_STRUCT`.
+    fn members(&mut self) -> Result<Vec<Member>, SourceError> {
+        self.expect_keyword("STRUCT")?;
+        let mut members = Vec::new();
+        while !self.eat_keyword("END_STRUCT") {
+            members.push(self.member()?);
+        }
+        Ok(members)
+    }
+
+    /// Parses `Name [{attributes}] : Type [:= value];`.
+    fn member(&mut self) -> Result<Member, SourceError> {
+        let line = self.line();
+        let name = self.name()?;
+        self.attributes()?;
+        self.expect_symbol(":")?;
+        let decl = self.decl()?;
+        let values = match self.eat_symbol(":=") {
+            true => self.values()?,
+            false => Vec::new(),
+        };
+        // the `;` after an `END_STRUCT` is optional
+        let ends_with_struct = match &decl {
+            Decl::Array { element, .. } => matches!(**element, Decl::Struct(_)),
+            decl => matches!(decl, Decl::Struct(_)),
+        };
+        if !self.eat_symbol(";") && !ends_with_struct {
+            return Err(self.error("';'"));
+        }
+        Ok(Member {
+            name,
+            decl,
+            values,
+            description: self.trailing_comment(),
+            line,
+        })
+    }
+
+    fn decl(&mut self) -> Result<Decl, SourceError> {
+        let line = self.line();
+        if self.is_keyword("STRUCT") {
+            return Ok(Decl::Struct(self.members()?));
+        }
+        if self.eat_keyword("ARRAY") {
+            self.expect_symbol("[")?;
+            let mut dimensions = Vec::new();
+            loop {
+                let lower = self.integer()?;
+                self.expect_symbol("..")?;
+                let upper = self.integer()?;
+                if upper < lower {
+                    return Err(SourceError::InvalidBounds { line, lower, upper });
+                }
+                dimensions.push(ArrayBounds { lower, upper });
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+            self.expect_symbol("]")?;
+            self.expect_keyword("OF")?;
+            if self.is_keyword("ARRAY") {
+                return Err(SourceError::NestedArray { line });
+            }
+            let [bounds] = dimensions[..] else {
+                return Err(SourceError::MultiDimArray { line });
+            };
+            let element = Box::new(self.decl()?);
+            return Ok(Decl::Array { bounds, element });
+        }
+        if self.is_keyword("UDT") {
+            return Ok(Decl::Udt(self.name()?));
+        }
+        match self.next() {
+            Some(Token::Quoted(name)) => Ok(Decl::Udt(name)),
+            Some(Token::Ident(name)) => {
+                let data_type =
+                    elementary_type(&name).ok_or(SourceError::UnknownType { line, name })?;
+                let mut length = None;
+                if matches!(data_type, DataType::STRING | DataType::WSTRING) && self.eat_symbol("[")
+                {
+                    let value = self.integer()?;
+                    let max = data_type.max_length().unwrap_or_default();
+                    length = Some(
+                        u16::try_from(value)
+                            .ok()
+                            .filter(|value| *value <= max)
+                            .ok_or_else(|| SourceError::Syntax {
+                                line,
+                                found: format!("the length {value}"),
+                                expected: format!("a string length between 0 and {max}"),
+                            })?,
+                    );
+                    self.expect_symbol("]")?;
+                }
+                Ok(Decl::Elementary { data_type, length })
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.error("a data type"))
+            }
+        }
+    }
+}
+
+/// Maps the name of an elementary TIA Portal type (`Int`, `Real`, `Time_Of_Day`...) to its
+/// `DataType`.
+fn elementary_type(name: &str) -> Option<DataType> {
+    let name = name.to_ascii_uppercase();
+    if name == "STRUCT" || name == "UDT" {
+        return None;
+    }
+    DataType::from_name(&name)
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, fs::File, io::BufReader};
+
+use calamine::{Data, Reader, Xlsx, XlsxError};
+use custom_error::custom_error;
+
+use crate::types::Register;
+use crate::utils::{insert_symbol, symbol_register, SymbolTableError};
+
+custom_error! {
+/// Error raised when a PLC tag table cannot be imported.
+pub TagTableError
+    Xlsx{ err: XlsxError } = "Workbook read error : {err}",
+    MissingColumn{ sheet: String, column: String } = "The sheet {sheet} has no {column} column",
+    Tag{ err: SymbolTableError } = "{err}",
+}
+
+impl From<XlsxError> for TagTableError {
+    fn from(value: XlsxError) -> Self {
+        TagTableError::Xlsx { err: value }
+    }
+}
+
+impl From<SymbolTableError> for TagTableError {
+    fn from(value: SymbolTableError) -> Self {
+        TagTableError::Tag { err: value }
+    }
+}
+
+/// Name of the sheet holding the tags in the workbooks exported by TIA Portal.
+const TAGS_SHEET: &str = "PLC Tags";
+
+/// Headers of the columns, in English and German.
+const NAME_COLUMN: [&str; 1] = ["Name"];
+const DATA_TYPE_COLUMN: [&str; 2] = ["Data Type", "Datentyp"];
+const ADDRESS_COLUMN: [&str; 2] = ["Logical Address", "Logische Adresse"];
+const COMMENT_COLUMN: [&str; 2] = ["Comment", "Kommentar"];
+
+/// Imports the registers of a PLC tag table exported by TIA Portal as an Excel workbook.
+///
+/// The tags are read from the `PLC Tags` sheet (the first sheet if there is none), whose first
+/// row holds the headers of the columns. The `Name`, `Data Type`, `Logical Address` (`%I0.0`,
+/// `%MW10`...) and `Comment` columns, or their German counterparts, become the name, type,
+/// address and description of the registers, the other columns are ignored. The tags of
+/// timers and counters are skipped, as well as the rows without name.
+///
+/// Parameters :
+///
+/// * `input` (`File`) - the `.xlsx` workbook exported by TIA Portal
+///
+/// Errors :
+/// - `Xlsx` if the workbook cannot be read.
+/// - `MissingColumn` if the name, data type or address column is missing.
+/// - `Tag` if a tag has an invalid address or type, or if its name is already used by a
+///   previous row, its `line` is the row in the sheet.
+pub fn get_defs_from_tag_table(input: File) -> Result<HashMap<String, Register>, TagTableError> {
+    let mut workbook = Xlsx::new(BufReader::new(input))?;
+    let sheets = workbook.sheet_names();
+    let sheet = sheets
+        .iter()
+        .find(|sheet| sheet.eq_ignore_ascii_case(TAGS_SHEET))
+        .or(sheets.first())
+        .cloned()
+        .unwrap_or_else(|| TAGS_SHEET.to_string());
+    let range = workbook.worksheet_range(&sheet)?;
+    let mut rows = range.rows();
+    let header = rows.next().unwrap_or_default();
+    let column = |names: &[&str]| {
+        header.iter().position(|cell| {
+            let cell = cell.to_string();
+            names
+                .iter()
+                .any(|name| cell.trim().eq_ignore_ascii_case(name))
+        })
+    };
+    let required = |names: &[&str]| {
+        column(names).ok_or_else(|| TagTableError::MissingColumn {
+            sheet: sheet.clone(),
+            column: names[0].to_string(),
+        })
+    };
+    let name_column = required(&NAME_COLUMN)?;
+    let data_type_column = required(&DATA_TYPE_COLUMN)?;
+    let address_column = required(&ADDRESS_COLUMN)?;
+    let comment_column = column(&COMMENT_COLUMN);
+    // rows of the sheet are numbered from 1, the header is the first row of the range
+    let first_row = range.start().map_or(1, |(row, _)| row as usize + 1);
+    let mut m = HashMap::<String, Register>::new();
+    for (i, row) in rows.enumerate() {
+        let cell = |column: usize| {
+            row.get(column)
+                .filter(|cell| !matches!(cell, Data::Empty))
+                .map(|cell| cell.to_string().trim().to_string())
+                .unwrap_or_default()
+        };
+        let name = cell(name_column);
+        if name.is_empty() {
+            continue;
+        }
+        let comment = comment_column.map(cell).unwrap_or_default();
+        let line = first_row + i + 1;
+        let tag = symbol_register(
+            line,
+            name,
+            &cell(address_column),
+            &cell(data_type_column),
+            comment,
+        )?;
+        if let Some(reg) = tag {
+            insert_symbol(&mut m, line, reg)?;
+        }
+    }
+    Ok(m)
+}
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
+use crate::datetime::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
 use crate::errors::S7Error;
+use crate::namespace::SEPARATOR;
 use crate::s7_connexion::S7Connexion;
-use crate::types::RegisterValue;
+use crate::types::{DataType, Register, RegisterValue};
 use crate::S7Device;
 
 use industrial_device::errors::IndustrialDeviceError;
@@ -31,12 +33,20 @@ impl IndustrialDevice for S7Device {
     /// 
     /// The `dump_registers` method is returning a `Result` containing a `HashMap<String, Value>` or an
     /// `IndustrialDeviceError`.
+    ///
+    /// The elements of array registers and the fields of struct registers are listed one by one
+    /// (`Name[index]`, `Name.field`), as `Value` has no array nor map variant.
     async fn dump_registers(&mut self) -> Result<HashMap<String, Value>, IndustrialDeviceError> {
         let vals = S7Connexion::dump_registers(self).await?;
-        Ok(vals
-            .iter()
-            .map(|(name, val)| (name.clone(), Into::<Value>::into(*val)))
-            .collect())
+        let mut res = HashMap::with_capacity(vals.len());
+        for (name, val) in vals {
+            let reg = self
+                .get_register_by_name(&name)
+                .ok_or(S7Error::RegisterDoesNotExistsError)?
+                .clone();
+            flatten(&reg, name, val, &mut res)?;
+        }
+        Ok(res)
     }
 
     /// The function `read_register_by_name` reads a register by name of an S7 device .
@@ -50,7 +60,9 @@ impl IndustrialDevice for S7Device {
     /// The function `read_register_by_name` is returning a `Result` containing a `Value` or an
     /// `IndustrialDeviceError`.
     async fn read_register_by_name(&mut self, name: &str) -> Result<Value, IndustrialDeviceError> {
-        Ok(S7Connexion::read_register_by_name(self, name).await?.into())
+        S7Connexion::read_register_by_name(self, name)
+            .await?
+            .try_into()
     }
 
     /// The function `write_register_by_name` writes a register value by name.
@@ -67,12 +79,30 @@ impl IndustrialDevice for S7Device {
     /// The `write_register_by_name` function is returning a `Result` enum with the possible outcomes
     /// being `Ok(())` if the operation is successful or an `Err(IndustrialDeviceError)` if an error
     /// occurs during the operation.
+    ///
+    /// The value is converted to the data type of the register, so that e.g. a `Value::U16` can be
+    /// written to a `BYTE` register as long as it fits. Whole arrays cannot be written through
+    /// `Value`, their elements can be written one by one (`Name[index]`), and the same goes for
+    /// structs and their fields (`Name.field`). Registers with a scaling take their engineering
+    /// value, as a float or an integer.
     async fn write_register_by_name(
         &mut self,
         name: &str,
         value: &Value,
     ) -> Result<(), IndustrialDeviceError> {
-        let val: RegisterValue = value.clone().try_into()?;
+        let reg = self
+            .resolve_register(name)
+            .ok_or(S7Error::RegisterDoesNotExistsError)?;
+        if reg.array.is_some() || reg.data_type == DataType::STRUCT {
+            return Err(IndustrialDeviceError::WrongValueType {
+                val: format!("{value:?} for {}", reg.name),
+            });
+        }
+        let data_type = match reg.scaling {
+            Some(_) => DataType::LREAL,
+            None => reg.data_type,
+        };
+        let val: RegisterValue = (value.clone(), data_type).try_into()?;
         Ok(S7Connexion::write_register_by_name(self, name, &val).await?)
     }
 }
@@ -86,35 +116,131 @@ impl From<S7Error> for IndustrialDeviceError {
             S7Error::DeviceNotConnectedError => IndustrialDeviceError::DeviceNotConnectedError {
                 err: Box::new(value),
             },
+            S7Error::UnsupportedAreaError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
             S7Error::MismatchedRegisterLengthError => IndustrialDeviceError::RequestError {
                 err: Box::new(value),
             },
             S7Error::RegisterDoesNotExistsError => IndustrialDeviceError::RequestError {
                 err: Box::new(value),
             },
+            S7Error::AddressOverflowError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
+            S7Error::RegisterAlreadyExistsError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
+            S7Error::GroupDoesNotExistsError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
             S7Error::InvalidRegisterValue => IndustrialDeviceError::RequestError {
                 err: Box::new(value),
             },
+            S7Error::StringTooLongError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
+            S7Error::InvalidStringLength { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
+            S7Error::WriteOnlyRegisterError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
+            S7Error::ReadOnlyRegisterError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
+            S7Error::OutOfLimitsError { .. } => IndustrialDeviceError::RequestError {
+                err: Box::new(value),
+            },
         }
     }
 }
 
-impl From<RegisterValue> for Value {
-    fn from(value: RegisterValue) -> Self {
-        match value {
+impl TryFrom<RegisterValue> for Value {
+    /// Converts a register value to the generic `Value`, each type of register always gives the
+    /// same variant.
+    ///
+    /// `Value` has no 8 bits, `i64` nor `f64` variants: 8 bits values are widened to 16 bits,
+    /// `LINT` values and durations (in milliseconds) are given as `S32` and `LREAL` values,
+    /// as every scaled value, as `Float32` rounded to the nearest `f32`. Characters, strings,
+    /// dates and times are given as `Enum`, dates and times in their ISO 8601 representation.
+    ///
+    /// Errors :
+    /// `WrongValueType` if the value does not fit its variant (a `LINT` beyond the `i32` range, an
+    /// `LREAL` beyond the `f32` range), and for arrays and structs whose elements and fields are
+    /// read one by one (`Name[index]`, `Name.field`).
+    fn try_from(value: RegisterValue) -> Result<Self, IndustrialDeviceError> {
+        let wrong_type = || IndustrialDeviceError::WrongValueType {
+            val: format!("{value:?}"),
+        };
+        let res = match value {
+            RegisterValue::S8(val) => Value::S16(val.into()),
+            RegisterValue::U8(val) => Value::U16(val.into()),
             RegisterValue::S16(val) => Value::S16(val),
+            RegisterValue::U16(val) => Value::U16(val),
             RegisterValue::S32(val) => Value::S32(val),
+            RegisterValue::U32(val) => Value::U32(val),
+            RegisterValue::S64(val) => Value::S32(i32::try_from(val).map_err(|_| wrong_type())?),
+            RegisterValue::Duration(val) => {
+                Value::S32(i32::try_from(val.num_milliseconds()).map_err(|_| wrong_type())?)
+            }
+            RegisterValue::U64(val) => Value::U64(val),
             RegisterValue::Float32(val) => Value::Float32(val),
+            RegisterValue::Float64(val) if val.is_finite() && val.abs() > f64::from(f32::MAX) => {
+                return Err(wrong_type())
+            }
+            RegisterValue::Float64(val) => Value::Float32(val as f32),
             RegisterValue::Boolean(val) => Value::Boolean(val),
+            RegisterValue::Char(val) => Value::Enum(val.to_string()),
+            RegisterValue::String(ref val) => Value::Enum(val.clone()),
+            RegisterValue::Date(val) => Value::Enum(val.to_string()),
+            RegisterValue::TimeOfDay(val) => Value::Enum(val.to_string()),
+            RegisterValue::DateTime(val) => Value::Enum(val.to_string()),
+            RegisterValue::Array(_) | RegisterValue::Struct(_) => return Err(wrong_type()),
+        };
+        Ok(res)
+    }
+
+    type Error = IndustrialDeviceError;
+}
+
+/// Converts the value of a register to `Value`s, the elements of arrays and the fields of
+/// structs are converted one by one and named `Name[index]` and `Name.field`.
+fn flatten(
+    reg: &Register,
+    name: String,
+    value: RegisterValue,
+    out: &mut HashMap<String, Value>,
+) -> Result<(), IndustrialDeviceError> {
+    match value {
+        RegisterValue::Array(vals) => {
+            let lower = reg.array.map_or(0, |bounds| bounds.lower);
+            for (index, val) in (lower..).zip(vals) {
+                let element = reg.element(index).unwrap_or_else(|_| reg.clone());
+                flatten(&element, format!("{name}[{index}]"), val, out)?;
+            }
+        }
+        RegisterValue::Struct(vals) => {
+            for (field, val) in vals {
+                let sub = reg.field(&field).unwrap_or_else(|_| reg.clone());
+                flatten(&sub, format!("{name}{SEPARATOR}{field}"), val, out)?;
+            }
+        }
+        val => {
+            out.insert(name, val.try_into()?);
         }
     }
+    Ok(())
 }
 
 impl TryFrom<Value> for RegisterValue {
     fn try_from(value: Value) -> Result<Self, IndustrialDeviceError> {
         let res = match value {
             Value::S16(val) => RegisterValue::S16(val),
+            Value::U16(val) => RegisterValue::U16(val),
             Value::S32(val) => RegisterValue::S32(val),
+            Value::U32(val) => RegisterValue::U32(val),
+            Value::U64(val) => RegisterValue::U64(val),
             Value::Float32(val) => RegisterValue::Float32(val),
             Value::Boolean(val) => RegisterValue::Boolean(val),
             _ => {
@@ -128,3 +254,95 @@ impl TryFrom<Value> for RegisterValue {
 
     type Error = IndustrialDeviceError;
 }
+
+impl TryFrom<(Value, DataType)> for RegisterValue {
+    /// Converts a generic `Value` to the register value matching `data_type`.
+    ///
+    /// Integers are range checked, floats are accepted for `REAL`/`LREAL` registers only,
+    /// `CHAR` registers take a one character `Value::Enum` and `STRING`/`WSTRING` registers
+    /// take any `Value::Enum`. Dates and times are parsed from their ISO 8601 representation
+    /// in a `Value::Enum`, durations are given as an integer number of milliseconds.
+    fn try_from((value, data_type): (Value, DataType)) -> Result<Self, IndustrialDeviceError> {
+        let wrong_type = || IndustrialDeviceError::WrongValueType {
+            val: format!("{value:?} for {data_type:?}"),
+        };
+        let int: Option<i128> = match &value {
+            Value::U16(val) => Some((*val).into()),
+            Value::U32(val) => Some((*val).into()),
+            Value::U64(val) => Some((*val).into()),
+            Value::U128(val) => i128::try_from(*val).ok(),
+            Value::S16(val) => Some((*val).into()),
+            Value::S32(val) => Some((*val).into()),
+            _ => None,
+        };
+        let res = match (data_type, &value) {
+            (DataType::BOOL, Value::Boolean(val)) => RegisterValue::Boolean(*val),
+            (DataType::FLOAT, Value::Float32(val)) => RegisterValue::Float32(*val),
+            (DataType::LREAL, Value::Float32(val)) => RegisterValue::Float64((*val).into()),
+            (DataType::CHAR, Value::Enum(val)) => {
+                let mut chars = val.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(val), None) => RegisterValue::Char(val),
+                    _ => return Err(wrong_type()),
+                }
+            }
+            (DataType::STRING | DataType::WSTRING, Value::Enum(val)) => {
+                RegisterValue::String(val.clone())
+            }
+            (DataType::DATE, Value::Enum(val)) => {
+                RegisterValue::Date(val.parse::<NaiveDate>().map_err(|_| wrong_type())?)
+            }
+            (DataType::TOD, Value::Enum(val)) => {
+                RegisterValue::TimeOfDay(val.parse::<NaiveTime>().map_err(|_| wrong_type())?)
+            }
+            (DataType::DT | DataType::DTL, Value::Enum(val)) => {
+                RegisterValue::DateTime(val.parse::<NaiveDateTime>().map_err(|_| wrong_type())?)
+            }
+            (data_type, _) => {
+                let int = int.ok_or_else(wrong_type)?;
+                match data_type {
+                    DataType::BYTE | DataType::USINT => {
+                        RegisterValue::U8(int.try_into().map_err(|_| wrong_type())?)
+                    }
+                    DataType::SINT => RegisterValue::S8(int.try_into().map_err(|_| wrong_type())?),
+                    DataType::WORD | DataType::UINT => {
+                        RegisterValue::U16(int.try_into().map_err(|_| wrong_type())?)
+                    }
+                    DataType::INT16 => {
+                        RegisterValue::S16(int.try_into().map_err(|_| wrong_type())?)
+                    }
+                    DataType::DWORD | DataType::UDINT => {
+                        RegisterValue::U32(int.try_into().map_err(|_| wrong_type())?)
+                    }
+                    DataType::INT32 => {
+                        RegisterValue::S32(int.try_into().map_err(|_| wrong_type())?)
+                    }
+                    DataType::LWORD | DataType::ULINT => {
+                        RegisterValue::U64(int.try_into().map_err(|_| wrong_type())?)
+                    }
+                    DataType::LINT => RegisterValue::S64(int.try_into().map_err(|_| wrong_type())?),
+                    DataType::FLOAT => RegisterValue::Float32(int as f32),
+                    DataType::LREAL => RegisterValue::Float64(int as f64),
+                    DataType::TIME | DataType::LTIME | DataType::S5TIME => {
+                        let ms: i64 = int.try_into().map_err(|_| wrong_type())?;
+                        RegisterValue::Duration(
+                            TimeDelta::try_milliseconds(ms).ok_or_else(wrong_type)?,
+                        )
+                    }
+                    DataType::BOOL
+                    | DataType::CHAR
+                    | DataType::STRING
+                    | DataType::WSTRING
+                    | DataType::DATE
+                    | DataType::TOD
+                    | DataType::DT
+                    | DataType::DTL
+                    | DataType::STRUCT => return Err(wrong_type()),
+                }
+            }
+        };
+        Ok(res)
+    }
+
+    type Error = IndustrialDeviceError;
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+
+use custom_error::custom_error;
+
+use crate::formats::register_id;
+use crate::types::{
+    ArrayBounds, BitAddress, ByteAddress, DataType, MemoryArea, RegAddress, Register,
+};
+
+custom_error! {pub LayoutError
+    Overflow{ name: String } = "The member {name} ends past the 65535 bytes of a data block"
+}
+
+/// Position of the next free bit while laying out the members of a struct.
+///
+/// The position is kept on 32 bits, a member is placed only if it ends inside the 65535 bytes
+/// of a data block.
+#[derive(Default)]
+struct Cursor {
+    byte: u32,
+    bit: u8,
+}
+
+impl Cursor {
+    /// Moves to the start of the next byte if some bits of the current byte are used.
+    fn align_byte(&mut self) {
+        if self.bit > 0 {
+            self.byte += 1;
+            self.bit = 0;
+        }
+    }
+
+    /// Moves to the start of the next word (even byte).
+    fn align_word(&mut self) {
+        self.align_byte();
+        self.byte += self.byte % 2;
+    }
+
+    /// Places a member at the current position and moves after it.
+    ///
+    /// Errors :
+    /// `Overflow` if the member does not end inside the data block.
+    fn place(&mut self, reg: &mut Register) -> Result<(), LayoutError> {
+        let overflow = |reg: &Register| LayoutError::Overflow {
+            name: reg.name.clone(),
+        };
+        if reg.data_type == DataType::STRUCT {
+            layout(&mut reg.fields)?;
+        }
+        if reg.data_type == DataType::BOOL && reg.array.is_none() {
+            reg.addr = RegAddress::Bit(BitAddress {
+                area: MemoryArea::DataBlock,
+                db: 0,
+                byte: u16::try_from(self.byte).map_err(|_| overflow(reg))?,
+                bit: self.bit,
+            });
+            self.bit += 1;
+            if self.bit == 8 {
+                self.align_byte();
+            }
+            return Ok(());
+        }
+        let single_byte = matches!(
+            reg.data_type,
+            DataType::BYTE | DataType::CHAR | DataType::SINT | DataType::USINT
+        );
+        let word_aligned = reg.array.is_some() || !single_byte;
+        if word_aligned {
+            self.align_word();
+        } else {
+            self.align_byte();
+        }
+        let byte = u16::try_from(self.byte).map_err(|_| overflow(reg))?;
+        reg.addr = match reg.data_type {
+            DataType::BOOL => RegAddress::Bit(BitAddress {
+                area: MemoryArea::DataBlock,
+                db: 0,
+                byte,
+                bit: 0,
+            }),
+            _ => RegAddress::Byte(ByteAddress {
+                area: MemoryArea::DataBlock,
+                db: 0,
+                byte,
+            }),
+        };
+        let size = reg.checked_size().ok_or_else(|| overflow(reg))?;
+        self.byte += u32::from(size);
+        if self.byte > u32::from(u16::MAX) {
+            return Err(overflow(reg));
+        }
+        // the member following an array or a struct starts on a new word
+        if reg.array.is_some() || reg.data_type == DataType::STRUCT {
+            self.align_word();
+        }
+        Ok(())
+    }
+}
+
+/// Computes the addresses of the members of a struct (or of a whole data block) following
+/// the rules of the non-optimized blocks, the current addresses of the members are ignored.
+///
+/// * consecutive `BOOL` members share the bits of a byte,
+/// * `BYTE`, `CHAR`, `SINT` and `USINT` members start on the next byte,
+/// * the other members, arrays and structs start on the next word (even byte),
+/// * the member following an array or a struct starts on the next word,
+/// * the fields of structs are laid out recursively, relative to the start of their struct.
+///
+/// The addresses are in the data block `0`, see `set_db` to move top level members to their
+/// data block, or `layout_block` to do both.
+///
+/// Errors :
+/// `Overflow` if a member ends past the 65535 bytes of a data block.
+pub fn layout(members: &mut [Register]) -> Result<(), LayoutError> {
+    let mut cursor = Cursor::default();
+    for reg in members {
+        cursor.place(reg)?;
+    }
+    Ok(())
+}
+
+/// Moves the (top level) registers to the data block `db`, the fields keep their relative
+/// addresses.
+pub fn set_db(regs: &mut [Register], db: u16) {
+    for reg in regs {
+        match &mut reg.addr {
+            RegAddress::Byte(addr) => addr.db = db,
+            RegAddress::Bit(addr) => addr.db = db,
+        }
+    }
+}
+
+/// Member of a block or struct to lay out, its address is computed by `layout`.
+///
+/// Set the `length` of `STRING`/`WSTRING` members and the metadata (`unit`, `scaling`...) on
+/// the returned register.
+pub fn member(name: impl Into<String>, data_type: DataType) -> Register {
+    let placeholder = RegAddress::Byte(ByteAddress {
+        area: MemoryArea::DataBlock,
+        db: 0,
+        byte: 0,
+    });
+    Register::new(name, data_type, placeholder)
+}
+
+/// Array member of a block or struct to lay out, see `member`.
+pub fn array_member(
+    name: impl Into<String>,
+    data_type: DataType,
+    bounds: impl Into<ArrayBounds>,
+) -> Register {
+    let mut reg = member(name, data_type);
+    reg.array = Some(bounds.into());
+    reg
+}
+
+/// Struct member of a block or struct to lay out, its fields are laid out in their order.
+pub fn struct_member(name: impl Into<String>, fields: Vec<Register>) -> Register {
+    let mut reg = member(name, DataType::STRUCT);
+    reg.fields = fields;
+    reg
+}
+
+/// Lays out the members of the non-optimized data block `db`, in their order.
+///
+/// ```
+/// use s7_device::layout::{self, array_member, member, struct_member};
+/// use s7_device::types::DataType;
+///
+/// let regs = layout::layout_block(
+///     5,
+///     vec![
+///         member("Running", DataType::BOOL),
+///         member("Speed", DataType::INT16),
+///         array_member("Temperatures", DataType::FLOAT, (1, 4)),
+///         struct_member("Valve", vec![member("Open", DataType::BOOL)]),
+///     ],
+/// )
+/// .unwrap();
+/// assert_eq!(regs[1].addr.to_string(), "DB5.DBB2");
+/// println!("{}", layout::memory_map(&regs).unwrap());
+/// ```
+///
+/// Errors :
+/// `Overflow` if a member ends past the 65535 bytes of a data block.
+pub fn layout_block(db: u16, mut members: Vec<Register>) -> Result<Vec<Register>, LayoutError> {
+    layout(&mut members)?;
+    set_db(&mut members, db);
+    Ok(members)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Line of a memory map.
+///
+/// Properties:
+/// * `address`: address of the member, with the width of its type (`DB5.DBW2`).
+/// * `byte`, `bit`: offset of the member from the start of its memory area, the bit is `0`
+///   for the members on byte addresses.
+/// * `bits`: size of the member in bits.
+/// * `name`: name of the member, `Parent.field` for the fields of structs.
+/// * `data_type`: type of the member (`INT16`, `ARRAY[1..4] OF FLOAT`, `STRING[20]`).
+/// * `depth`: nesting level of the member, `0` for the top level registers.
+pub struct MemoryMapEntry {
+    pub address: String,
+    pub byte: u16,
+    pub bit: u8,
+    pub bits: u32,
+    pub name: String,
+    pub data_type: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Memory map of registers, printed as a table in the order of the addresses.
+///
+/// Properties:
+/// * `entries`: the registers and the fields of their structs, sorted by address.
+/// * `size`: size in bytes of the data block holding the registers (up to the end of the
+///   last register, rounded to an even size).
+pub struct MemoryMap {
+    pub entries: Vec<MemoryMapEntry>,
+    pub size: u16,
+}
+
+/// Builds the memory map of registers, e.g. laid out by `layout_block`.
+///
+/// The fields of struct registers are listed below their struct, the elements of arrays are
+/// not listed.
+///
+/// Errors :
+/// `Overflow` if a register ends past the 65535 bytes of a memory area.
+pub fn memory_map(regs: &[Register]) -> Result<MemoryMap, LayoutError> {
+    let mut regs: Vec<&Register> = regs.iter().collect();
+    regs.sort_by_key(|reg| {
+        let (area, db, byte, bit) = match &reg.addr {
+            RegAddress::Byte(addr) => (addr.area, addr.db, addr.byte, 0),
+            RegAddress::Bit(addr) => (addr.area, addr.db, addr.byte, addr.bit),
+        };
+        (area as u8, db, byte, bit)
+    });
+    let mut map = MemoryMap::default();
+    for reg in regs {
+        let end = reg
+            .checked_size()
+            .and_then(|size| reg.byte_offset().checked_add(size))
+            .ok_or_else(|| LayoutError::Overflow {
+                name: reg.name.clone(),
+            })?;
+        map.size = map.size.max(end.saturating_add(end % 2));
+        push_entries(&mut map.entries, reg, 0);
+    }
+    Ok(map)
+}
+
+/// Adds a register and the fields of its struct to the entries.
+fn push_entries(entries: &mut Vec<MemoryMapEntry>, reg: &Register, depth: usize) {
+    let bits = match (reg.data_type, reg.array) {
+        (DataType::BOOL, None) => 1,
+        _ => u32::from(reg.size()) * 8,
+    };
+    let element = match reg.data_type {
+        DataType::STRING | DataType::WSTRING => {
+            format!("{}[{}]", reg.data_type.name(), reg.string_length())
+        }
+        data_type => data_type.name().to_string(),
+    };
+    let data_type = match reg.array {
+        Some(bounds) => format!("ARRAY[{}..{}] OF {element}", bounds.lower, bounds.upper),
+        None => element,
+    };
+    entries.push(MemoryMapEntry {
+        address: register_id(reg),
+        byte: reg.byte_offset(),
+        bit: match &reg.addr {
+            RegAddress::Bit(addr) => addr.bit,
+            RegAddress::Byte(_) => 0,
+        },
+        bits,
+        name: reg.name.clone(),
+        data_type,
+        depth,
+    });
+    if reg.array.is_none() {
+        for field in &reg.fields {
+            if let Ok(field) = reg.field(&field.name) {
+                push_entries(entries, &field, depth + 1);
+            }
+        }
+    }
+}
+
+impl fmt::Display for MemoryMap {
+    /// One line per entry with its address, its offset and size in the Siemens notation
+    /// (`2.0`, `0.1` for a bit) and its name indented by its depth, then the size of the block.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| format!("{}{}", "  ".repeat(entry.depth), entry.name))
+            .collect();
+        let address_width = self
+            .entries
+            .iter()
+            .map(|e| e.address.len())
+            .max()
+            .unwrap_or(0);
+        let name_width = names.iter().map(String::len).max().unwrap_or(0);
+        writeln!(
+            f,
+            "{:address_width$}  {:>7}  {:>7}  {:name_width$}  Type",
+            "Address", "Offset", "Size", "Name"
+        )?;
+        for (entry, name) in self.entries.iter().zip(&names) {
+            let offset = format!("{}.{}", entry.byte, entry.bit);
+            let size = format!("{}.{}", entry.bits / 8, entry.bits % 8);
+            writeln!(
+                f,
+                "{:address_width$}  {:>7}  {:>7}  {:name_width$}  {}",
+                entry.address, offset, size, name, entry.data_type
+            )?;
+        }
+        write!(f, "Size : {} bytes", self.size)
+    }
+}
@@ -0,0 +1,144 @@
+use std::{collections::HashMap, fs::File, path::Path};
+
+use custom_error::custom_error;
+use serde::{Deserialize, Serialize};
+
+use crate::formats::{self, FormatError};
+use crate::namespace::SEPARATOR;
+use crate::types::{MemoryArea, RegAddress, Register};
+
+#[derive(Serialize, Deserialize)]
+/// Format of a manifest, a JSON file gathering several definition files
+///
+/// ```json
+/// {
+///   "include": [
+///     { "path": "station.json", "prefix": "Line1.Station1" },
+///     { "path": "station.json", "prefix": "Line1.Station2", "db_offset": 100 },
+///     { "path": "line.yaml" }
+///   ]
+/// }
+/// ```
+///
+/// Parameters :
+///
+/// * `include` (`Vec<IncludeFormat>`) - the included definition files, in order
+pub struct ManifestFormat {
+    pub include: Vec<IncludeFormat>,
+}
+
+#[derive(Serialize, Deserialize)]
+/// Format of a definition file included by a manifest
+///
+/// Parameters :
+///
+/// * `path` (`String`) - the path of the definition file, relative to the manifest, in any of
+///   the formats of `formats::DefinitionFormat` (given by the extension)
+/// * `prefix` (`Option<String>`) - the level added before the names of the registers
+///   (`Station1` turns `Motor.Speed` into `Station1.Motor.Speed`)
+/// * `db_offset` (`u16`) - the number added to the data block of the registers, `0` by
+///   default, the other memory areas are kept
+pub struct IncludeFormat {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub db_offset: u16,
+}
+
+custom_error! {pub ManifestError
+    Io{ err: std::io::Error } = "Read error : {err}",
+    SerdeJson{ err: serde_json::Error } = "Json parse error : {err}",
+    Include{ path: String, err: FormatError } = "Cannot load {path} : {err}",
+    Collision{ name: String, first: String, second: String } = "The register {name} of {second} is already defined by {first}",
+    DbOverflow{ name: String, path: String } = "The data block of the register {name} of {path} exceeds 65535 after the offset"
+}
+
+impl From<std::io::Error> for ManifestError {
+    fn from(value: std::io::Error) -> Self {
+        ManifestError::Io { err: value }
+    }
+}
+
+impl From<serde_json::Error> for ManifestError {
+    fn from(value: serde_json::Error) -> Self {
+        ManifestError::SerdeJson { err: value }
+    }
+}
+
+/// Reads the registers of all the definition files included by a manifest.
+///
+/// The includes are loaded in order, with the prefix and the data block offset of their entry.
+/// The same file can be included several times, e.g. once per station with a different
+/// prefix and data block.
+///
+/// Parameters :
+///
+/// * `path` (`impl AsRef<Path>`) - the path of the manifest, the included paths are relative
+///   to its directory
+///
+/// Returns :
+/// The registers of all the included files by name, with their prefixed names.
+///
+/// Errors :
+/// - `Io` or `SerdeJson` if the manifest cannot be read.
+/// - `Include` if an included file cannot be loaded, among others if it defines the same
+///   name twice (`JsonReadError::DuplicateRegister`).
+/// - `Collision` if two included files define registers with the same (prefixed) name,
+///   instead of keeping one of them.
+/// - `DbOverflow` if the offset moves a register past the last data block.
+pub fn get_defs_from_manifest(
+    path: impl AsRef<Path>,
+) -> Result<HashMap<String, Register>, ManifestError> {
+    let path = path.as_ref();
+    let manifest: ManifestFormat = serde_json::from_reader(File::open(path)?)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut defs = HashMap::<String, Register>::new();
+    // included file defining each register, to report the collisions
+    let mut origins = HashMap::<String, String>::new();
+    for include in manifest.include {
+        let regs = formats::read_defs_from_path(dir.join(&include.path)).map_err(|err| {
+            ManifestError::Include {
+                path: include.path.clone(),
+                err,
+            }
+        })?;
+        let mut regs: Vec<Register> = regs.into_values().collect();
+        regs.sort_by(|a, b| a.name.cmp(&b.name));
+        for mut reg in regs {
+            if let Some(prefix) = include
+                .prefix
+                .as_deref()
+                .filter(|prefix| !prefix.is_empty())
+            {
+                reg.name = format!("{prefix}{SEPARATOR}{}", reg.name);
+            }
+            offset_db(&mut reg, include.db_offset).ok_or_else(|| ManifestError::DbOverflow {
+                name: reg.name.clone(),
+                path: include.path.clone(),
+            })?;
+            if let Some(first) = origins.get(&reg.name) {
+                return Err(ManifestError::Collision {
+                    name: reg.name,
+                    first: first.clone(),
+                    second: include.path,
+                });
+            }
+            origins.insert(reg.name.clone(), include.path.clone());
+            defs.insert(reg.name.clone(), reg);
+        }
+    }
+    Ok(defs)
+}
+
+/// Moves a register of a data block `offset` blocks further, `None` on overflow.
+fn offset_db(reg: &mut Register, offset: u16) -> Option<()> {
+    let (area, db) = match &mut reg.addr {
+        RegAddress::Byte(addr) => (addr.area, &mut addr.db),
+        RegAddress::Bit(addr) => (addr.area, &mut addr.db),
+    };
+    if area == MemoryArea::DataBlock {
+        *db = db.checked_add(offset)?;
+    }
+    Some(())
+}
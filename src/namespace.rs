@@ -0,0 +1,75 @@
+/// Separator of the levels of the hierarchical register names (`Line1.Station3.Motor.Speed`).
+pub const SEPARATOR: char = '.';
+
+/// Whether a register name matches a glob pattern.
+///
+/// The pattern is matched level by level :
+/// * `*` matches any sequence of characters inside a level (`Motor*`, `*`),
+/// * `?` matches one character inside a level,
+/// * `**` as a whole level matches any number of levels, including none.
+///
+/// `Line1.*.Motor.*` matches `Line1.Station3.Motor.Speed` but not `Line1.Motor.Speed`, and
+/// `Line1.**` matches every register below `Line1`.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split(SEPARATOR).collect();
+    let name: Vec<&str> = name.split(SEPARATOR).collect();
+    match_levels(&pattern, &name)
+}
+
+/// Returns the level following `prefix` in `name`, `None` if `name` is not below `prefix`.
+///
+/// An empty prefix designates the root, whose children are the first levels of the names.
+pub fn child<'a>(prefix: &str, name: &'a str) -> Option<&'a str> {
+    let rest = match prefix {
+        "" => name,
+        prefix => name.strip_prefix(prefix)?.strip_prefix(SEPARATOR)?,
+    };
+    rest.split(SEPARATOR)
+        .next()
+        .filter(|level| !level.is_empty())
+}
+
+fn match_levels(pattern: &[&str], name: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((&"**", rest)) => (0..=name.len()).any(|skip| match_levels(rest, &name[skip..])),
+        Some((level, rest)) => name
+            .split_first()
+            .is_some_and(|(first, others)| match_level(level, first) && match_levels(rest, others)),
+    }
+}
+
+/// Matches a single level against a pattern made of `*`, `?` and literal characters.
+fn match_level(pattern: &str, level: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let level: Vec<char> = level.chars().collect();
+    let (mut p, mut l) = (0, 0);
+    // position of the last `*` in the pattern and of the level character it stopped at
+    let mut star: Option<(usize, usize)> = None;
+    while l < level.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, l));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                l += 1;
+            }
+            Some(c) if *c == level[l] => {
+                p += 1;
+                l += 1;
+            }
+            _ => match star {
+                // let the last `*` swallow one more character
+                Some((star_p, star_l)) => {
+                    star = Some((star_p, star_l + 1));
+                    p = star_p + 1;
+                    l = star_l + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
@@ -0,0 +1,118 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use custom_error::custom_error;
+
+use crate::errors::S7Error;
+use crate::formats::{self, DefinitionFormat, FormatError};
+use crate::types::Register;
+use crate::validation::{self, ValidationReport};
+
+custom_error! {pub ReloadError
+    Io{ err: std::io::Error } = "Read error : {err}",
+    UnknownFormat{ path: String } = "Unknown definition format of {path}",
+    Definitions{ err: FormatError } = "{err}",
+    Invalid{ report: ValidationReport } = "Invalid definitions :\n{report}",
+    Unsupported{ name: String, err: S7Error } = "The register {name} cannot be accessed : {err}"
+}
+
+impl From<std::io::Error> for ReloadError {
+    fn from(value: std::io::Error) -> Self {
+        ReloadError::Io { err: value }
+    }
+}
+
+impl From<FormatError> for ReloadError {
+    fn from(value: FormatError) -> Self {
+        ReloadError::Definitions { err: value }
+    }
+}
+
+/// Polls a definition file and loads it again when its content changes, see `S7Device::reload`.
+///
+/// The file is not watched by the operating system : each call to `poll` reads the whole file
+/// and compares a hash of the bytes read with the one of the last successful load. The
+/// comparison does not depend on the modification time, a change within the resolution of the
+/// file system clock is seen, but the poll period should suit the size of the file.
+///
+/// ```no_run
+/// # use s7_device::{reload::DefinitionsPoller, S7Device};
+/// # async fn run(mut dev: S7Device) {
+/// let mut poller = DefinitionsPoller::new("registers.json").unwrap();
+/// loop {
+///     if let Err(err) = dev.reload(&mut poller) {
+///         log::error!("{err}, the previous definitions are kept");
+///     }
+///     // read and write the registers
+/// }
+/// # }
+/// ```
+pub struct DefinitionsPoller {
+    path: PathBuf,
+    format: DefinitionFormat,
+    hash: Option<u64>,
+}
+
+impl DefinitionsPoller {
+    /// Polls the definition file `path`, in the format given by its extension.
+    ///
+    /// The file is not read yet, the first `poll` loads it.
+    ///
+    /// Errors :
+    /// Returns `UnknownFormat` if the extension is not one of a `DefinitionFormat`.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, ReloadError> {
+        let path = path.as_ref();
+        let format =
+            DefinitionFormat::from_path(path).ok_or_else(|| ReloadError::UnknownFormat {
+                path: path.display().to_string(),
+            })?;
+        Ok(DefinitionsPoller {
+            path: path.to_path_buf(),
+            format,
+            hash: None,
+        })
+    }
+
+    /// Path of the polled file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads and validates the file if its content changed since the last successful load.
+    ///
+    /// JSON files are checked by `validation::validate_str`, the other formats by
+    /// `validation::validate_defs`, warnings are accepted.
+    ///
+    /// Returns :
+    /// The new registers, `None` if the file did not change.
+    ///
+    /// Errors :
+    /// Returns a `ReloadError` if the file cannot be read, parsed or is invalid. The change is
+    /// kept : the file is loaded again at the next call, until it loads successfully.
+    pub fn poll(&mut self) -> Result<Option<HashMap<String, Register>>, ReloadError> {
+        let text = fs::read(&self.path)?;
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.hash == Some(hash) {
+            return Ok(None);
+        }
+        if self.format == DefinitionFormat::Json {
+            let report = validation::validate_str(&String::from_utf8_lossy(&text));
+            if !report.is_valid() {
+                return Err(ReloadError::Invalid { report });
+            }
+        }
+        let defs = formats::read_defs(text.as_slice(), self.format)?;
+        let report = validation::validate_defs(&defs);
+        if !report.is_valid() {
+            return Err(ReloadError::Invalid { report });
+        }
+        self.hash = Some(hash);
+        Ok(Some(defs))
+    }
+}
@@ -12,7 +12,19 @@ pub trait S7Connexion {
         &mut self,
         reg: &Register,
     ) -> impl std::future::Future<Output = Result<RegisterValue, S7Error>> + Send;
+    fn read_raw_register(
+        &mut self,
+        reg: &Register,
+    ) -> impl std::future::Future<Output = Result<RegisterValue, S7Error>> + Send;
+    fn read_raw_register_by_name(
+        &mut self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<RegisterValue, S7Error>> + Send;
     fn get_register_by_name(&self, name: &str) -> Option<&Register>;
+    fn browse(&self) -> Vec<&Register>;
+    fn browse_tag(&self, tag: &str) -> Vec<&Register>;
+    fn find_registers(&self, pattern: &str) -> Vec<&Register>;
+    fn browse_children(&self, prefix: &str) -> Vec<String>;
     fn read_register_by_name(
         &mut self,
         name: &str,
@@ -31,6 +43,29 @@ pub trait S7Connexion {
         name: &str,
         val: &RegisterValue,
     ) -> impl std::future::Future<Output = Result<(), S7Error>> + Send;
+    fn write_raw_register(
+        &mut self,
+        reg: &Register,
+        val: &RegisterValue,
+    ) -> impl std::future::Future<Output = Result<(), S7Error>> + Send;
+    fn write_raw_register_by_name(
+        &mut self,
+        name: &str,
+        val: &RegisterValue,
+    ) -> impl std::future::Future<Output = Result<(), S7Error>> + Send;
+    fn read_matching(
+        &mut self,
+        pattern: &str,
+    ) -> impl std::future::Future<Output = Result<HashMap<String, RegisterValue>, S7Error>> + Send;
+    fn read_group(
+        &mut self,
+        group: &str,
+    ) -> impl std::future::Future<Output = Result<HashMap<String, RegisterValue>, S7Error>> + Send;
+    fn write_group(
+        &mut self,
+        group: &str,
+        vals: &HashMap<String, RegisterValue>,
+    ) -> impl std::future::Future<Output = Result<(), S7Error>> + Send;
     fn dump_registers(
         &mut self,
     ) -> impl std::future::Future<Output = Result<HashMap<String, RegisterValue>, S7Error>> + Send;
@@ -2,15 +2,28 @@ use std::{collections::HashMap, net::SocketAddr};
 
 use s7_client::{s7_comm::DataItemVal, Area, Options, S7Client};
 
+pub mod address;
+pub mod datetime;
 pub mod errors;
+pub mod formats;
+pub mod import;
 pub mod industrial_device;
+pub mod layout;
+pub mod manifest;
+pub mod namespace;
+pub mod reload;
 pub mod s7_connexion;
+pub mod schema;
 pub mod types;
 pub mod utils;
+pub mod validation;
 
 use errors::S7Error;
+use reload::{DefinitionsPoller, ReloadError};
 use s7_connexion::S7Connexion;
-use types::{BitAddress, ByteAddress, RegAddress, Register, RegisterValue};
+use types::{
+    BitAddress, ByteAddress, LimitPolicy, MemoryArea, RegAddress, Register, RegisterValue,
+};
 
 /// The `S7Device` struct represents a device with options, a client, and registers in Rust.
 /// 
@@ -22,10 +35,12 @@ use types::{BitAddress, ByteAddress, RegAddress, Register, RegisterValue};
 /// * `registers`: The `registers` property in the `S7Device` struct is a HashMap that stores key-value
 /// pairs where the key is a `String` and the value is a `Register` struct. This allows you to store and
 /// access registers by their unique names within the device.
+/// * `limit_policy`: What to do with written values out of the limits of their register.
 pub struct S7Device {
     option: Options,
     client: Option<S7Client>,
     registers: HashMap<String, Register>,
+    limit_policy: LimitPolicy,
 }
 
 impl S7Device {
@@ -51,6 +66,212 @@ impl S7Device {
             option,
             client: None,
             registers: regs,
+            limit_policy: LimitPolicy::default(),
+        }
+    }
+
+    /// Sets what to do with written values out of the `min`/`max` limits of their register,
+    /// they are rejected by default.
+    pub fn set_limit_policy(&mut self, policy: LimitPolicy) {
+        self.limit_policy = policy;
+    }
+
+    /// Adds a register to the device, the connection is kept.
+    ///
+    /// Errors :
+    /// `RegisterAlreadyExistsError` if a register has the same name, see `replace_register`.
+    pub fn add_register(&mut self, reg: Register) -> Result<(), S7Error> {
+        if self.registers.contains_key(&reg.name) {
+            return Err(S7Error::RegisterAlreadyExistsError { name: reg.name });
+        }
+        self.registers.insert(reg.name.clone(), reg);
+        Ok(())
+    }
+
+    /// Removes the register `name` from the device and returns it.
+    ///
+    /// Errors :
+    /// `RegisterDoesNotExistsError` if the device has no such register.
+    pub fn remove_register(&mut self, name: &str) -> Result<Register, S7Error> {
+        self.registers
+            .remove(name)
+            .ok_or(S7Error::RegisterDoesNotExistsError)
+    }
+
+    /// Replaces the register of the same name and returns the previous one.
+    ///
+    /// Errors :
+    /// `RegisterDoesNotExistsError` if the device has no register of this name, see
+    /// `add_register`.
+    pub fn replace_register(&mut self, reg: Register) -> Result<Register, S7Error> {
+        let previous = self
+            .registers
+            .get_mut(&reg.name)
+            .ok_or(S7Error::RegisterDoesNotExistsError)?;
+        Ok(std::mem::replace(previous, reg))
+    }
+
+    /// Replaces all the registers of the device at once and returns the previous ones.
+    pub fn set_registers(&mut self, regs: HashMap<String, Register>) -> HashMap<String, Register> {
+        std::mem::replace(&mut self.registers, regs)
+    }
+
+    /// Applies the definitions of a polled file if its content changed since the last load.
+    ///
+    /// The new definitions are validated, and each register is checked against the areas the
+    /// connection can access, before replacing all the registers at once. The registers are
+    /// untouched on error. The connection is kept in both cases.
+    ///
+    /// Returns :
+    /// Whether the registers were replaced.
+    ///
+    /// Errors :
+    /// - the errors of `DefinitionsPoller::poll`.
+    /// - `Unsupported` if a register is in an area the connection cannot access (peripheral I/O).
+    pub fn reload(&mut self, poller: &mut DefinitionsPoller) -> Result<bool, ReloadError> {
+        let regs = match poller.poll()? {
+            Some(regs) => regs,
+            None => return Ok(false),
+        };
+        for reg in regs.values() {
+            let size = s7_client::DataSizeType::Byte {
+                addr: reg.byte_offset(),
+                len: reg.size(),
+            };
+            to_area(reg.area(), 0, size).map_err(|err| ReloadError::Unsupported {
+                name: reg.name.clone(),
+                err,
+            })?;
+        }
+        self.set_registers(regs);
+        Ok(true)
+    }
+
+    /// Reads the raw bytes of a register in a single request.
+    ///
+    /// Errors :
+    /// - `DeviceNotConnectedError` if the client is not connected.
+    /// - `MismatchedRegisterLengthError` if the address does not match the expected type.
+    /// - `UnsupportedAreaError` for the peripheral inputs and outputs.
+    async fn read_bytes(&mut self, reg: &Register) -> Result<Vec<u8>, S7Error> {
+        let area = match (&reg.data_type, &reg.addr) {
+            (types::DataType::BOOL, RegAddress::Bit(addr)) => to_area(
+                addr.area,
+                addr.db,
+                s7_client::DataSizeType::Byte {
+                    addr: addr.byte,
+                    len: reg.size(),
+                },
+            )?,
+            (types::DataType::BOOL, RegAddress::Byte(_)) | (_, RegAddress::Bit(_)) => {
+                return Err(S7Error::MismatchedRegisterLengthError)
+            }
+            (_, RegAddress::Byte(addr)) => to_area(
+                addr.area,
+                addr.db,
+                s7_client::DataSizeType::Byte {
+                    addr: addr.byte,
+                    len: reg.size(),
+                },
+            )?,
+        };
+        let client = self
+            .client
+            .as_mut()
+            .ok_or(S7Error::DeviceNotConnectedError)?;
+
+        let rec_val = client.read(vec![area]).await?;
+        let raw: Option<&DataItemVal> = rec_val.first();
+
+        let bytes: Vec<u8> = raw
+            .ok_or(S7Error::MismatchedRegisterLengthError)?
+            .data
+            .clone();
+        Ok(bytes)
+    }
+
+    /// Writes raw bytes at the given position of a memory area.
+    ///
+    /// Data blocks are written with the dedicated DB requests, the inputs, outputs and flags
+    /// with a generic area write.
+    ///
+    /// Errors :
+    /// - `UnsupportedAreaError` for the peripheral inputs and outputs.
+    /// - `DeviceNotConnectedError` if the client is not connected.
+    async fn write_bytes(&mut self, addr: &ByteAddress, value: Vec<u8>) -> Result<(), S7Error> {
+        let size = s7_client::DataSizeType::Byte {
+            addr: addr.byte,
+            len: value.len() as u16,
+        };
+        let area = to_area(addr.area, addr.db, size)?;
+        let client = self
+            .client
+            .as_mut()
+            .ok_or(S7Error::DeviceNotConnectedError)?;
+        match addr.area {
+            MemoryArea::DataBlock => client.write_db_bytes(addr.db, addr.byte, &value).await?,
+            _ => client.write(area, value).await?,
+        };
+        Ok(())
+    }
+
+    /// Lists the registers of a group, i.e. holding the group name in their tags, sorted by name.
+    ///
+    /// Errors :
+    /// - `GroupDoesNotExistsError` if no register belongs to the group.
+    fn group_registers(&self, group: &str) -> Result<Vec<Register>, S7Error> {
+        let regs: Vec<Register> = self.browse_tag(group).into_iter().cloned().collect();
+        if regs.is_empty() {
+            return Err(S7Error::GroupDoesNotExistsError {
+                name: group.to_string(),
+            });
+        }
+        Ok(regs)
+    }
+
+    /// Resolves a register from its name.
+    ///
+    /// Besides the registers of the map, the elements of array registers can be
+    /// resolved with the `Name[index]` syntax and the fields of struct registers with the
+    /// `Name.field` syntax, both can be combined (`Motors[2].Speed`).
+    fn resolve_register(&self, name: &str) -> Option<Register> {
+        if let Some(reg) = self.registers.get(name) {
+            return Some(reg.clone());
+        }
+        if let Some((base, index)) = name
+            .strip_suffix(']')
+            .and_then(|name| name.rsplit_once('['))
+        {
+            return self
+                .resolve_register(base)?
+                .element(index.trim().parse().ok()?)
+                .ok();
+        }
+        let (base, field) = name.rsplit_once('.')?;
+        self.resolve_register(base)?.field(field).ok()
+    }
+}
+
+/// Builds the `s7_client` area targeted by an address.
+///
+/// Parameters :
+/// * `area`: memory area of the address.
+/// * `db`: data block number, only used for `MemoryArea::DataBlock`.
+/// * `size`: position and size of the data inside the area.
+///
+/// Errors :
+/// `UnsupportedAreaError` for the peripheral inputs and outputs, `s7_client` has no request
+/// for them and the process image areas hold different values.
+fn to_area(area: MemoryArea, db: u16, size: s7_client::DataSizeType) -> Result<Area, S7Error> {
+    match area {
+        MemoryArea::DataBlock => Ok(Area::DataBausteine(db, size)),
+        MemoryArea::Input => Ok(Area::ProcessInput(size)),
+        MemoryArea::Output => Ok(Area::ProcessOutput(size)),
+        MemoryArea::Flag => Ok(Area::Merker(size)),
+        MemoryArea::PeripheralInput | MemoryArea::PeripheralOutput => {
+            Err(S7Error::UnsupportedAreaError {
+                area: area.to_string(),
+            })
         }
     }
 }
@@ -65,7 +286,6 @@ impl S7Connexion for S7Device {
         Ok(())
     }
 
-
     /// Reads the value of a specific register from the S7 PLC.
     ///
     ///
@@ -73,68 +293,49 @@ impl S7Connexion for S7Device {
     /// - `reg`: description of the register to read (`Register`).
     ///
     /// Returns :
-    /// The register value as a `RegisterValue`.
+    /// The register value as a `RegisterValue`, converted to its engineering value
+    /// (`Float64`) when the register has a scaling.
     ///
     /// Errors :
     /// - `DeviceNotConnectedError` if the client is not connected.
     /// - `MismatchedRegisterLengthError` if the address does not match
     ///   the expected type (e.g. `BOOL` on a `ByteAddress`).
+    /// - `WriteOnlyRegisterError` if the register is write-only, checked before anything is
+    ///   sent to the PLC.
     async fn read_register(&mut self, reg: &Register) -> Result<RegisterValue, S7Error> {
-        self.client
-            .as_ref()
-            .ok_or(S7Error::DeviceNotConnectedError)?;
-
-        let area = match reg.data_type {
-            types::DataType::BOOL => match &reg.addr {
-                RegAddress::Byte(_val) => return Err(S7Error::MismatchedRegisterLengthError),
-                RegAddress::Bit(addr) => Area::DataBausteine(
-                    addr.db,
-                    s7_client::DataSizeType::Byte {
-                        addr: addr.byte,
-                        len: 1,
-                    },
-                ),
-            },
-            types::DataType::FLOAT => match &reg.addr {
-                RegAddress::Byte(addr) => Area::DataBausteine(
-                    addr.db,
-                    s7_client::DataSizeType::Real {
-                        addr: addr.byte,
-                        len: 4,
-                    },
-                ),
-                RegAddress::Bit(_) => return Err(S7Error::MismatchedRegisterLengthError),
-            },
-            types::DataType::INT32 => match &reg.addr {
-                RegAddress::Byte(addr) => Area::DataBausteine(
-                    addr.db,
-                    s7_client::DataSizeType::Int {
-                        addr: addr.byte,
-                        len: 4,
-                    },
-                ),
-                RegAddress::Bit(_) => return Err(S7Error::MismatchedRegisterLengthError),
-            },
-            types::DataType::INT16 => match &reg.addr {
-                RegAddress::Byte(addr) => Area::DataBausteine(
-                    addr.db,
-                    s7_client::DataSizeType::Int {
-                        addr: addr.byte,
-                        len: 2,
-                    },
-                ),
-                RegAddress::Bit(_) => return Err(S7Error::MismatchedRegisterLengthError),
-            },
-        };
-
-        let rec_val = self.client.as_mut().unwrap().read(vec![area]).await?;
-        let raw: Option<&DataItemVal> = rec_val.get(0);
+        let raw = self.read_raw_register(reg).await?;
+        reg.scale(raw)
+    }
 
-        let bytes: Vec<u8> = raw.unwrap().data.clone();
+    /// Reads the raw value of a specific register, ignoring its scaling.
+    ///
+    /// Parameters :
+    /// - `reg`: description of the register to read (`Register`).
+    ///
+    /// Errors :
+    /// Same as `read_register`.
+    async fn read_raw_register(&mut self, reg: &Register) -> Result<RegisterValue, S7Error> {
+        if !reg.access.is_readable() {
+            return Err(S7Error::WriteOnlyRegisterError {
+                name: reg.name.clone(),
+            });
+        }
+        let bytes = self.read_bytes(reg).await?;
         let conv: RegisterValue = (bytes, reg.clone()).try_into()?;
         Ok(conv)
     }
 
+    /// Reads the raw value of a register by its logical name, ignoring its scaling.
+    ///
+    /// Errors :
+    /// - `RegisterDoesNotExistsError` if no register with this name is defined.
+    async fn read_raw_register_by_name(&mut self, name: &str) -> Result<RegisterValue, S7Error> {
+        match self.resolve_register(name) {
+            Some(reg) => self.read_raw_register(&reg).await,
+            None => Err(S7Error::RegisterDoesNotExistsError),
+        }
+    }
+
     /// Reads a register by its logical name defined in the configuration.
     ///
     /// Parameters :
@@ -143,10 +344,13 @@ impl S7Connexion for S7Device {
     /// Returns :
     /// The register value (`RegisterValue`).
     ///
+    /// Elements of array registers can be read with the `Name[index]` syntax and fields of
+    /// struct registers with the `Name.field` syntax.
+    ///
     /// Errors :
     /// - `RegisterDoesNotExistsError` if no register with this name is defined.
     async fn read_register_by_name(&mut self, name: &str) -> Result<RegisterValue, S7Error> {
-        let reg = self.get_register_by_name(name).cloned();
+        let reg = self.resolve_register(name);
 
         match reg {
             Some(reg) => self.read_register(&reg).await,
@@ -165,6 +369,52 @@ impl S7Connexion for S7Device {
         self.registers.get(name)
     }
 
+    /// Lists the definitions of all the registers of the device, sorted by name.
+    ///
+    /// The definitions carry the metadata of the registers (description, unit, access mode,
+    /// limits and tags) to present them in a user interface.
+    fn browse(&self) -> Vec<&Register> {
+        let mut regs: Vec<&Register> = self.registers.values().collect();
+        regs.sort_by(|a, b| a.name.cmp(&b.name));
+        regs
+    }
+
+    /// Lists the definitions of the registers holding the tag `tag`, sorted by name.
+    fn browse_tag(&self, tag: &str) -> Vec<&Register> {
+        self.browse()
+            .into_iter()
+            .filter(|reg| reg.tags.iter().any(|t| t == tag))
+            .collect()
+    }
+
+    /// Lists the definitions of the registers whose name matches a glob pattern, sorted by name.
+    ///
+    /// Register names are hierarchical, their levels separated by dots
+    /// (`Line1.Station3.Motor.Speed`). `*` and `?` match inside a level and `**` matches any
+    /// number of levels, see `namespace::matches` (e.g. `Line1.*.Motor.*`, `Line1.Station3.**`).
+    fn find_registers(&self, pattern: &str) -> Vec<&Register> {
+        self.browse()
+            .into_iter()
+            .filter(|reg| namespace::matches(pattern, &reg.name))
+            .collect()
+    }
+
+    /// Lists the distinct levels directly below `prefix` in the register names, sorted.
+    ///
+    /// With the registers `Line1.Station3.Motor.Speed` and `Line1.Station4.Valve`, the children
+    /// of `Line1` are `Station3` and `Station4`, an empty prefix lists the first levels (`Line1`).
+    fn browse_children(&self, prefix: &str) -> Vec<String> {
+        let mut children: Vec<String> = self
+            .registers
+            .keys()
+            .filter_map(|name| namespace::child(prefix, name))
+            .map(str::to_string)
+            .collect();
+        children.sort();
+        children.dedup();
+        children
+    }
+
     /// Reads the value of **all known registers** of the device.
     ///
     /// Returns :
@@ -179,6 +429,9 @@ impl S7Connexion for S7Device {
 
     /// Reads multiple registers at once.
     ///
+    /// The write-only registers are skipped, they are missing from the result. The bulk reads
+    /// (`dump_registers`, `read_matching`, `read_group`) rely on it.
+    ///
     /// Parameters :
     /// - `regs`: list of registers to read.
     ///
@@ -192,7 +445,7 @@ impl S7Connexion for S7Device {
         regs: &[Register],
     ) -> Result<HashMap<String, RegisterValue>, S7Error> {
         let mut res: HashMap<String, RegisterValue> = HashMap::with_capacity(regs.len());
-        for reg in regs {
+        for reg in regs.iter().filter(|reg| reg.access.is_readable()) {
             let val = self.read_register(reg).await?;
             res.insert(reg.name.clone(), val);
         }
@@ -203,7 +456,18 @@ impl S7Connexion for S7Device {
     ///
     /// Depending on the data type, the write is performed as:
     /// - `BOOL` → single bit write (`BitAddress`).
-    /// - `FLOAT`, `INT16`, `INT32` → byte/block write (`ByteAddress`).
+    /// - `BOOL` array and `STRUCT` → read-modify-write of the bytes of the register, so that
+    ///   the neighbouring bits and the fields missing from the value are kept.
+    /// - every other type → byte/block write (`ByteAddress`), strings are written with their
+    ///   header up to their actual length and arrays in a single request.
+    ///
+    /// Data blocks are written with the dedicated DB requests, the inputs, outputs and flags
+    /// with a generic area write. The peripheral inputs and outputs cannot be written.
+    ///
+    /// The value of a register with a scaling is an engineering value, it is converted back
+    /// to the raw value of the register before being written. Read-only registers and values
+    /// out of the limits of the register are rejected (or clamped, see `set_limit_policy`)
+    /// before anything is sent to the PLC.
     ///
     /// Parameters :
     /// - `reg`: description of the register.
@@ -211,30 +475,88 @@ impl S7Connexion for S7Device {
     ///
     /// Errors :
     /// - `DeviceNotConnectedError` if the client is not connected.
-    /// - `MismatchedRegisterLengthError` if the address does not match
+    /// - `MismatchedRegisterLengthError` if the address or the size of the value does not match
     ///   the expected type.
+    /// - `StringTooLongError` if a string exceeds the capacity of the register.
+    /// - `InvalidRegisterValue` if the raw value of a scaled register is out of its range.
+    /// - `ReadOnlyRegisterError` if the register (or a written field) is read-only.
+    /// - `OutOfLimitsError` if the value is out of the limits of the register.
+    /// - `UnsupportedAreaError` for the peripheral inputs and outputs.
     async fn write_register(&mut self, reg: &Register, val: &RegisterValue) -> Result<(), S7Error> {
+        // a read-only register is refused whatever the value
+        reg.check_access(val)?;
+        let val = reg.apply_limits(val.clone(), self.limit_policy)?;
+        let raw = reg.unscale(val)?;
+        self.write_raw_register(reg, &raw).await
+    }
+
+    /// Writes the raw value of a specific register, ignoring its scaling and its limits.
+    ///
+    /// The access mode of the register is still enforced.
+    ///
+    /// Parameters :
+    /// - `reg`: description of the register.
+    /// - `val`: raw value to be written (`RegisterValue`).
+    ///
+    /// Errors :
+    /// Same as `write_register`.
+    async fn write_raw_register(
+        &mut self,
+        reg: &Register,
+        val: &RegisterValue,
+    ) -> Result<(), S7Error> {
+        reg.check_access(val)?;
         if self.client.is_none() {
             return Err(S7Error::DeviceNotConnectedError);
         }
 
-        match reg.data_type {
-            types::DataType::BOOL => {
+        match (reg.data_type, &reg.array) {
+            (types::DataType::BOOL, None) => {
                 let addr: BitAddress = reg.addr.clone().try_into()?;
-                self.client
-                    .as_mut()
-                    .unwrap()
-                    .write_db_bit(addr.db, addr.byte, addr.bit, val.clone().try_into()?)
-                    .await?
+                let value: bool = val.clone().try_into()?;
+                let client = self.client.as_mut().unwrap();
+                match addr.area {
+                    MemoryArea::DataBlock => {
+                        client
+                            .write_db_bit(addr.db, addr.byte, addr.bit, value)
+                            .await?
+                    }
+                    area => {
+                        let size = s7_client::DataSizeType::Bit {
+                            addr: addr.byte,
+                            bit_addr: addr.bit.into(),
+                        };
+                        client
+                            .write(to_area(area, addr.db, size)?, vec![value.into()])
+                            .await?
+                    }
+                }
             }
-            types::DataType::FLOAT | types::DataType::INT32 | types::DataType::INT16 => {
+            _ if reg.needs_merge() => {
+                let addr = match &reg.addr {
+                    RegAddress::Byte(addr) => addr.clone(),
+                    RegAddress::Bit(addr) => ByteAddress {
+                        area: addr.area,
+                        db: addr.db,
+                        byte: addr.byte,
+                    },
+                };
+                let current = self.read_bytes(reg).await?;
+                let value = reg.merge(val.clone(), current)?;
+                self.write_bytes(&addr, value).await?
+            }
+            (data_type, array) => {
                 let addr: ByteAddress = reg.addr.clone().try_into()?;
-                let value: Vec<u8> = val.clone().try_into()?;
-                self.client
-                    .as_mut()
-                    .unwrap()
-                    .write_db_bytes(addr.db, addr.byte, &value)
-                    .await?
+                let value: Vec<u8> = reg.encode(val.clone())?;
+                let is_string = array.is_none()
+                    && matches!(
+                        data_type,
+                        types::DataType::STRING | types::DataType::WSTRING
+                    );
+                if !is_string && value.len() != reg.size() as usize {
+                    return Err(S7Error::MismatchedRegisterLengthError);
+                }
+                self.write_bytes(&addr, value).await?
             }
         };
         Ok(())
@@ -246,6 +568,9 @@ impl S7Connexion for S7Device {
     /// - `name`: symbolic name of the register.
     /// - `val`: value to be written.
     ///
+    /// Elements of array registers can be written with the `Name[index]` syntax and fields of
+    /// struct registers with the `Name.field` syntax.
+    ///
     /// Errors :
     /// - `RegisterDoesNotExistsError` if the register does not exist.
     async fn write_register_by_name(
@@ -253,11 +578,90 @@ impl S7Connexion for S7Device {
         name: &str,
         val: &RegisterValue,
     ) -> Result<(), S7Error> {
-        let reg = self.get_register_by_name(name).cloned();
+        let reg = self.resolve_register(name);
 
         match reg {
             Some(reg) => self.write_register(&reg, val).await,
             None => return Err(S7Error::RegisterDoesNotExistsError),
         }
     }
-}
\ No newline at end of file
+    /// Writes the raw value of a register identified by its logical name, ignoring its scaling.
+    ///
+    /// Errors :
+    /// - `RegisterDoesNotExistsError` if the register does not exist.
+    async fn write_raw_register_by_name(
+        &mut self,
+        name: &str,
+        val: &RegisterValue,
+    ) -> Result<(), S7Error> {
+        match self.resolve_register(name) {
+            Some(reg) => self.write_raw_register(&reg, val).await,
+            None => Err(S7Error::RegisterDoesNotExistsError),
+        }
+    }
+    /// Reads all the registers whose name matches a glob pattern (see `find_registers`).
+    ///
+    /// Returns :
+    /// - `HashMap<String, RegisterValue>`: mapping of register name → value, empty if no
+    ///   register matches.
+    ///
+    /// Errors :
+    /// Propagates errors from `read_registers`.
+    async fn read_matching(
+        &mut self,
+        pattern: &str,
+    ) -> Result<HashMap<String, RegisterValue>, S7Error> {
+        let regs: Vec<Register> = self.find_registers(pattern).into_iter().cloned().collect();
+        self.read_registers(&regs).await
+    }
+
+    /// Reads all the registers of a group.
+    ///
+    /// Groups are declared in the `groups` of the definition file, any tag of the registers
+    /// can also be used as a group.
+    ///
+    /// Returns :
+    /// - `HashMap<String, RegisterValue>`: mapping of register name → value.
+    ///
+    /// Errors :
+    /// - `GroupDoesNotExistsError` if no register belongs to the group.
+    /// Propagates errors from `read_registers`.
+    async fn read_group(&mut self, group: &str) -> Result<HashMap<String, RegisterValue>, S7Error> {
+        let regs = self.group_registers(group)?;
+        self.read_registers(&regs).await
+    }
+
+    /// Writes registers of a group.
+    ///
+    /// Every register of `vals` must belong to the group, this is checked before anything
+    /// is written. The registers are then written one by one, in the order of their names.
+    ///
+    /// Parameters :
+    /// - `group`: name of the group.
+    /// - `vals`: mapping of register name → value, not necessarily covering the whole group.
+    ///
+    /// Errors :
+    /// - `GroupDoesNotExistsError` if no register belongs to the group.
+    /// - `RegisterDoesNotExistsError` if a register of `vals` is not in the group.
+    /// Propagates errors from `write_register`, the registers written before the error keep
+    /// their new value.
+    async fn write_group(
+        &mut self,
+        group: &str,
+        vals: &HashMap<String, RegisterValue>,
+    ) -> Result<(), S7Error> {
+        let regs = self.group_registers(group)?;
+        if vals
+            .keys()
+            .any(|name| !regs.iter().any(|reg| &reg.name == name))
+        {
+            return Err(S7Error::RegisterDoesNotExistsError);
+        }
+        for reg in regs {
+            if let Some(val) = vals.get(&reg.name) {
+                self.write_register(&reg, val).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use serde_json::{json, Value};
+
+use crate::types::DataType;
+
+/// JSON Schema (draft 2020-12) of the register definition files read by
+/// `utils::get_defs_from_json`, for editors and tools generating definitions.
+///
+/// The schema accepts both forms of the files, the plain list of registers and the document
+/// with `types`, `registers` and `groups`. It checks the structure of the file only, the
+/// addresses and the consistency of the registers (address width, struct types, overlaps)
+/// are checked by `validation::validate`. As the parser, it ignores unknown properties.
+///
+/// The schema is published at the root of the repository (`registers.schema.json`).
+///
+/// ```
+/// let schema = s7_device::schema::definitions_schema();
+/// let text = serde_json::to_string_pretty(&schema).unwrap();
+/// ```
+pub fn definitions_schema() -> Value {
+    // properties shared by the registers and the struct fields
+    let common = json!({
+        "type": { "$ref": "#/$defs/dataType" },
+        "length": {
+            "description": "Capacity in characters of a STRING/WSTRING, 254 by default",
+            "type": "integer", "minimum": 0, "maximum": 65535
+        },
+        "array": { "$ref": "#/$defs/arrayBounds" },
+        "struct": {
+            "description": "Name of the struct type of a STRUCT register, declared in `types`",
+            "type": "string"
+        },
+        "scaling": { "$ref": "#/$defs/scaling" },
+        "unit": { "type": "string" },
+        "description": { "type": "string" },
+        "access": { "enum": ["RO", "RW", "WO", "read-only", "read-write", "write-only"] },
+        "min": { "type": "number" },
+        "max": { "type": "number" },
+        "tags": { "type": "array", "items": { "type": "string" } },
+        "byte_order": { "enum": ["ABCD", "DCBA", "BADC", "CDAB"] }
+    });
+    let mut register = common.clone();
+    register["name"] = json!({ "type": "string" });
+    register["id"] = json!({
+        "description": "Address of the register (`DB1.DBD0`, `DB1.DBX4.0`, `MW10`, `I0.0`...)",
+        "type": "string"
+    });
+    let mut field = common;
+    field["name"] = json!({ "type": "string" });
+    field["offset"] = json!({
+        "description": "Offset in bytes from the start of the struct",
+        "type": "integer", "minimum": 0, "maximum": 65535
+    });
+    field["bit"] = json!({ "type": "integer", "minimum": 0, "maximum": 7 });
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "S7 register definitions",
+        "oneOf": [
+            { "$ref": "#/$defs/registers" },
+            {
+                "type": "object",
+                "anyOf": [{ "required": ["registers"] }, { "required": ["instances"] }],
+                "properties": {
+                    "types": {
+                        "description": "Struct types, by name",
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "array",
+                            "items": { "$ref": "#/$defs/field" }
+                        }
+                    },
+                    "registers": { "$ref": "#/$defs/registers" },
+                    "groups": {
+                        "description": "Names of the registers of each group",
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "array",
+                            "items": { "type": "string" }
+                        }
+                    },
+                    "instances": {
+                        "description": "Instances of templates (struct types of `types`), expanded into registers",
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/instance" }
+                    }
+                }
+            }
+        ],
+        "$defs": {
+            "registers": { "type": "array", "items": { "$ref": "#/$defs/register" } },
+            "register": {
+                "type": "object",
+                "required": ["name", "id", "type"],
+                "properties": register
+            },
+            "field": {
+                "type": "object",
+                "required": ["name", "type", "offset"],
+                "properties": field
+            },
+            "instance": {
+                "type": "object",
+                "required": ["template", "prefix", "db"],
+                "properties": {
+                    "template": { "type": "string" },
+                    "prefix": { "type": "string" },
+                    "db": { "type": "integer", "minimum": 0, "maximum": 65535 },
+                    "offset": { "type": "integer", "minimum": 0, "maximum": 65535 }
+                }
+            },
+            "dataType": { "enum": DataType::names().collect::<Vec<_>>() },
+            "arrayBounds": {
+                "description": "Lower and upper bounds of the array, both included",
+                "type": "array",
+                "prefixItems": [{ "type": "integer" }, { "type": "integer" }],
+                "minItems": 2,
+                "maxItems": 2
+            },
+            "scaling": {
+                "anyOf": [
+                    {
+                        "type": "object",
+                        "required": ["raw", "eng"],
+                        "properties": {
+                            "raw": { "$ref": "#/$defs/range" },
+                            "eng": { "$ref": "#/$defs/range" }
+                        }
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "factor": { "type": "number" },
+                            "offset": { "type": "number" }
+                        }
+                    }
+                ]
+            },
+            "range": {
+                "type": "array",
+                "items": { "type": "number" },
+                "minItems": 2,
+                "maxItems": 2
+            }
+        }
+    })
+}
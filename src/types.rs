@@ -1,24 +1,321 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
 use serde::{Deserialize, Serialize};
 
+use crate::address::{self, Address, AddressError, AddressWidth};
+use crate::datetime::{self, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
 use crate::errors::S7Error;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Elementary S7 data types a register can hold.
+///
+/// The historical names (`FLOAT`, `INT32`, `INT16`) are kept, the Siemens names
+/// (`REAL`, `DINT`, `INT`) are accepted as aliases, see `DataType::aliases`.
 pub enum DataType {
-    #[serde(alias = "BIT")]
     BOOL,
-    FLOAT,
-    INT32,
+    BYTE,
+    WORD,
+    DWORD,
+    LWORD,
+    SINT,
+    USINT,
     INT16,
+    UINT,
+    INT32,
+    UDINT,
+    LINT,
+    ULINT,
+    FLOAT,
+    LREAL,
+    CHAR,
+    STRING,
+    WSTRING,
+    DATE,
+    TIME,
+    TOD,
+    DT,
+    DTL,
+    S5TIME,
+    LTIME,
+    STRUCT,
+}
+
+impl DataType {
+    /// Every data type, in the order of their declaration.
+    pub const ALL: [DataType; 26] = [
+        DataType::BOOL,
+        DataType::BYTE,
+        DataType::WORD,
+        DataType::DWORD,
+        DataType::LWORD,
+        DataType::SINT,
+        DataType::USINT,
+        DataType::INT16,
+        DataType::UINT,
+        DataType::INT32,
+        DataType::UDINT,
+        DataType::LINT,
+        DataType::ULINT,
+        DataType::FLOAT,
+        DataType::LREAL,
+        DataType::CHAR,
+        DataType::STRING,
+        DataType::WSTRING,
+        DataType::DATE,
+        DataType::TIME,
+        DataType::TOD,
+        DataType::DT,
+        DataType::DTL,
+        DataType::S5TIME,
+        DataType::LTIME,
+        DataType::STRUCT,
+    ];
+
+    /// Name of the type in the definition files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DataType::BOOL => "BOOL",
+            DataType::BYTE => "BYTE",
+            DataType::WORD => "WORD",
+            DataType::DWORD => "DWORD",
+            DataType::LWORD => "LWORD",
+            DataType::SINT => "SINT",
+            DataType::USINT => "USINT",
+            DataType::INT16 => "INT16",
+            DataType::UINT => "UINT",
+            DataType::INT32 => "INT32",
+            DataType::UDINT => "UDINT",
+            DataType::LINT => "LINT",
+            DataType::ULINT => "ULINT",
+            DataType::FLOAT => "FLOAT",
+            DataType::LREAL => "LREAL",
+            DataType::CHAR => "CHAR",
+            DataType::STRING => "STRING",
+            DataType::WSTRING => "WSTRING",
+            DataType::DATE => "DATE",
+            DataType::TIME => "TIME",
+            DataType::TOD => "TOD",
+            DataType::DT => "DT",
+            DataType::DTL => "DTL",
+            DataType::S5TIME => "S5TIME",
+            DataType::LTIME => "LTIME",
+            DataType::STRUCT => "STRUCT",
+        }
+    }
+
+    /// Other names accepted for the type in the definition files.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            DataType::BOOL => &["BIT"],
+            DataType::INT16 => &["INT"],
+            DataType::INT32 => &["DINT"],
+            DataType::FLOAT => &["REAL"],
+            DataType::TOD => &["TIME_OF_DAY"],
+            DataType::DT => &["DATE_AND_TIME"],
+            DataType::STRUCT => &["UDT"],
+            _ => &[],
+        }
+    }
+
+    /// Every name accepted for a type in the definition files, the aliases included.
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        DataType::ALL.iter().flat_map(|data_type| {
+            std::iter::once(data_type.name()).chain(data_type.aliases().iter().copied())
+        })
+    }
+
+    /// Type named `name` (or one of its aliases), `None` if it is unknown.
+    pub fn from_name(name: &str) -> Option<DataType> {
+        DataType::ALL
+            .into_iter()
+            .find(|data_type| data_type.name() == name || data_type.aliases().contains(&name))
+    }
+}
+
+impl Serialize for DataType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for DataType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        DataType::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown data type `{name}`")))
+    }
+}
+
+/// Capacity of a `STRING`/`WSTRING` declared without length (`STRING` is `STRING[254]`).
+pub const DEFAULT_STRING_LENGTH: u16 = 254;
+
+/// Maximum capacity of a `WSTRING`.
+pub const MAX_WSTRING_LENGTH: u16 = 16382;
+
+impl DataType {
+    /// Size in bytes of a value of this type in the PLC memory.
+    ///
+    /// `BOOL` is reported as one byte, which is the amount of data read to extract the bit.
+    /// Strings are reported with their default capacity, see `Register::size` for the
+    /// size of a declared string.
+    pub fn size(&self) -> u16 {
+        self.size_with_length(DEFAULT_STRING_LENGTH)
+    }
+
+    /// Maximum capacity in characters of a string type, `None` for the other types.
+    ///
+    /// A `STRING` stores its capacity on one byte and is limited to 254 characters.
+    pub fn max_length(&self) -> Option<u16> {
+        match self {
+            DataType::STRING => Some(DEFAULT_STRING_LENGTH),
+            DataType::WSTRING => Some(MAX_WSTRING_LENGTH),
+            _ => None,
+        }
+    }
+
+    /// Whether the type is one of the date and time types handled by the `datetime` module.
+    pub fn is_time(&self) -> bool {
+        matches!(
+            self,
+            DataType::DATE
+                | DataType::TIME
+                | DataType::TOD
+                | DataType::DT
+                | DataType::DTL
+                | DataType::S5TIME
+                | DataType::LTIME
+        )
+    }
+
+    /// Whether the type holds a number (integers, bit strings and floats), i.e. a value that
+    /// can be scaled and limited.
+    pub fn is_numeric(&self) -> bool {
+        !self.is_time()
+            && !matches!(
+                self,
+                DataType::BOOL
+                    | DataType::CHAR
+                    | DataType::STRING
+                    | DataType::WSTRING
+                    | DataType::STRUCT
+            )
+    }
+
+    /// Size in bytes of a value of this type holding at most `length` characters.
+    ///
+    /// The string header (maximum length and actual length) is included: a `STRING[n]`
+    /// takes `n + 2` bytes and a `WSTRING[n]` takes `2 * n + 4` bytes.
+    /// `length` is ignored for the other types. The size of a `STRUCT` depends on its fields and
+    /// is reported as `0`, see `Register::size`.
+    pub fn size_with_length(&self, length: u16) -> u16 {
+        match self {
+            DataType::BOOL | DataType::BYTE | DataType::SINT | DataType::USINT | DataType::CHAR => {
+                1
+            }
+            DataType::WORD
+            | DataType::INT16
+            | DataType::UINT
+            | DataType::DATE
+            | DataType::S5TIME => 2,
+            DataType::DWORD
+            | DataType::INT32
+            | DataType::UDINT
+            | DataType::FLOAT
+            | DataType::TIME
+            | DataType::TOD => 4,
+            DataType::LWORD
+            | DataType::LINT
+            | DataType::ULINT
+            | DataType::LREAL
+            | DataType::DT
+            | DataType::LTIME => 8,
+            DataType::DTL => 12,
+            DataType::STRUCT => 0,
+            // saturated, the capacities beyond the size of a memory area are reported by the
+            // validation
+            DataType::STRING => length.saturating_add(2),
+            DataType::WSTRING => length.saturating_mul(2).saturating_add(4),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// The `RegisterValue` is defining different variants to represent values
 /// that can be stored in a register.
+///
+/// Bit strings (`BYTE`, `WORD`, `DWORD`, `LWORD`) are represented by the unsigned variant of the same size.
+/// Durations (`TIME`, `LTIME`, `S5TIME`) share the `Duration` variant and both `DT` and `DTL`
+/// are represented by `DateTime`. Array registers hold an `Array` of their elements and struct
+/// registers a `Struct` mapping the field names to their values.
+///
+/// Values are serialized tagged with their variant (`{"S16": -3}`), dates and times in the
+/// ISO 8601 format and durations as a number of nanoseconds.
 pub enum RegisterValue {
+    S8(i8),
+    U8(u8),
     S16(i16),
+    U16(u16),
     S32(i32),
+    U32(u32),
+    S64(i64),
+    U64(u64),
     Float32(f32),
+    Float64(f64),
     Boolean(bool),
+    Char(char),
+    String(String),
+    Date(NaiveDate),
+    TimeOfDay(NaiveTime),
+    DateTime(NaiveDateTime),
+    Duration(#[serde(with = "datetime::nanoseconds")] TimeDelta),
+    Array(Vec<RegisterValue>),
+    Struct(HashMap<String, RegisterValue>),
+}
+
+impl fmt::Display for RegisterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegisterValue::S8(val) => write!(f, "{val}"),
+            RegisterValue::U8(val) => write!(f, "{val}"),
+            RegisterValue::S16(val) => write!(f, "{val}"),
+            RegisterValue::U16(val) => write!(f, "{val}"),
+            RegisterValue::S32(val) => write!(f, "{val}"),
+            RegisterValue::U32(val) => write!(f, "{val}"),
+            RegisterValue::S64(val) => write!(f, "{val}"),
+            RegisterValue::U64(val) => write!(f, "{val}"),
+            RegisterValue::Float32(val) => write!(f, "{val}"),
+            RegisterValue::Float64(val) => write!(f, "{val}"),
+            RegisterValue::Boolean(val) => write!(f, "{val}"),
+            RegisterValue::Char(val) => write!(f, "{val}"),
+            RegisterValue::String(val) => write!(f, "{val}"),
+            RegisterValue::Date(val) => write!(f, "{val}"),
+            RegisterValue::TimeOfDay(val) => write!(f, "{val}"),
+            RegisterValue::DateTime(val) => write!(f, "{val}"),
+            RegisterValue::Duration(val) => write!(f, "{val}"),
+            RegisterValue::Array(vals) => {
+                write!(f, "[")?;
+                for (i, val) in vals.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{val}")?;
+                }
+                write!(f, "]")
+            }
+            RegisterValue::Struct(vals) => {
+                let mut names: Vec<&String> = vals.keys().collect();
+                names.sort();
+                write!(f, "{{")?;
+                for (i, name) in names.into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{name}: {}", vals[name])?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
 }
 
 impl TryFrom<RegisterValue> for bool {
@@ -43,47 +340,148 @@ impl TryFrom<RegisterValue> for i16 {
     }
 }
 
+impl TryFrom<RegisterValue> for f64 {
+    type Error = S7Error;
+
+    /// Converts any numeric value, 64 bits integers beyond 2^53 lose precision.
+    fn try_from(value: RegisterValue) -> Result<Self, Self::Error> {
+        match value {
+            RegisterValue::S8(val) => Ok(val.into()),
+            RegisterValue::U8(val) => Ok(val.into()),
+            RegisterValue::S16(val) => Ok(val.into()),
+            RegisterValue::U16(val) => Ok(val.into()),
+            RegisterValue::S32(val) => Ok(val.into()),
+            RegisterValue::U32(val) => Ok(val.into()),
+            RegisterValue::S64(val) => Ok(val as f64),
+            RegisterValue::U64(val) => Ok(val as f64),
+            RegisterValue::Float32(val) => Ok(val.into()),
+            RegisterValue::Float64(val) => Ok(val),
+            _ => Err(S7Error::InvalidRegisterValue),
+        }
+    }
+}
+
 impl TryFrom<RegisterValue> for Vec<u8> {
     type Error = S7Error;
 
     fn try_from(value: RegisterValue) -> Result<Self, Self::Error> {
         match value {
+            RegisterValue::S8(val) => Ok(Vec::from(val.to_be_bytes())),
+            RegisterValue::U8(val) => Ok(Vec::from(val.to_be_bytes())),
             RegisterValue::S16(val) => Ok(Vec::from(val.to_be_bytes())),
+            RegisterValue::U16(val) => Ok(Vec::from(val.to_be_bytes())),
             RegisterValue::S32(val) => Ok(Vec::from(val.to_be_bytes())),
+            RegisterValue::U32(val) => Ok(Vec::from(val.to_be_bytes())),
+            RegisterValue::S64(val) => Ok(Vec::from(val.to_be_bytes())),
+            RegisterValue::U64(val) => Ok(Vec::from(val.to_be_bytes())),
             RegisterValue::Float32(val) => Ok(Vec::from(val.to_be_bytes())),
-            RegisterValue::Boolean(_) => Err(S7Error::InvalidRegisterValue),
+            RegisterValue::Float64(val) => Ok(Vec::from(val.to_be_bytes())),
+            RegisterValue::Char(val) => match u8::try_from(val) {
+                Ok(val) => Ok(vec![val]),
+                Err(_) => Err(S7Error::InvalidRegisterValue),
+            },
+            RegisterValue::Boolean(_)
+            | RegisterValue::String(_)
+            | RegisterValue::Date(_)
+            | RegisterValue::TimeOfDay(_)
+            | RegisterValue::DateTime(_)
+            | RegisterValue::Duration(_)
+            | RegisterValue::Array(_)
+            | RegisterValue::Struct(_) => Err(S7Error::InvalidRegisterValue),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Memory area of the S7 PLC an address points into.
+///
+/// Variants :
+/// * `DataBlock` - a **Data Block** (DB), the DB number is carried by the address itself.
+/// * `Input` - process image of the inputs (`I`).
+/// * `Output` - process image of the outputs (`Q`).
+/// * `Flag` - flags / merkers (`M`).
+/// * `PeripheralInput` - peripheral inputs (`PI`).
+/// * `PeripheralOutput` - peripheral outputs (`PQ`).
+pub enum MemoryArea {
+    #[default]
+    #[serde(rename = "DB")]
+    DataBlock,
+    #[serde(rename = "I")]
+    Input,
+    #[serde(rename = "Q")]
+    Output,
+    #[serde(rename = "M")]
+    Flag,
+    #[serde(rename = "PI")]
+    PeripheralInput,
+    #[serde(rename = "PQ")]
+    PeripheralOutput,
+}
+
+impl fmt::Display for MemoryArea {
+    /// Formats the area with its English mnemonic (`DB`, `I`, `Q`, `M`, `PI`, `PQ`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = match self {
+            MemoryArea::DataBlock => "DB",
+            MemoryArea::Input => "I",
+            MemoryArea::Output => "Q",
+            MemoryArea::Flag => "M",
+            MemoryArea::PeripheralInput => "PI",
+            MemoryArea::PeripheralOutput => "PQ",
+        };
+        write!(f, "{mnemonic}")
+    }
+}
+
+impl MemoryArea {
+    /// Whether the area is one of the peripheral I/O areas (`PI`, `PQ`). They can be written
+    /// in the addresses but `s7_client` has no request to access them.
+    pub fn is_peripheral(&self) -> bool {
+        matches!(
+            self,
+            MemoryArea::PeripheralInput | MemoryArea::PeripheralOutput
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 /// The `RegAddress` is defining different variants to represent a register a `Byte adress` or a `Bit Adress`
+///
+/// It is displayed, parsed and serialized in the Siemens notation (`DB1.DBX0.0`, `MB20`), see
+/// `address::parse` for the accepted forms.
 pub enum RegAddress {
     Byte(ByteAddress),
     Bit(BitAddress),
 }
 
-
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 /// Represents a memory address at the **bit** level in an S7 controller.
 /// 
 ///
 /// Properties:
-/// * `db`: Number of the **Data Block** (DB) in which the address is located.
+/// * `area`: Memory area of the address, `DataBlock` when omitted.
+/// * `db`: Number of the **Data Block** (DB) in which the address is located (ignored outside of `DataBlock`).
 /// * `byte`: Index of the byte in the DB.
 /// * `bit`: Index of the bit in the byte (value between `0` and `7`).
 ///
 /// # Exemple
 /// ```rust
-/// let addr = BitAddress { db: 1, byte: 10, bit: 3 };
+/// use s7_device::types::{BitAddress, MemoryArea};
+///
+/// let addr = BitAddress { area: MemoryArea::DataBlock, db: 1, byte: 10, bit: 3 };
 /// /*  Represents DB1.DBX10.3 in Siemens notation */
+/// let addr = BitAddress { area: MemoryArea::Output, db: 0, byte: 4, bit: 1 };
+/// /*  Represents Q4.1 in Siemens notation */
 /// ```
 pub struct BitAddress {
+    #[serde(default)]
+    pub area: MemoryArea,
     pub db: u16,
     pub byte: u16,
     pub bit: u8,
 }
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 
 /// Represents a memory address at the **byte** level in an S7 PLC.
 /// 
@@ -91,19 +489,124 @@ pub struct BitAddress {
 ///  without targeting a specific bit.
 ///
 /// Properties:
-/// * `db`: **Data Block** (DB) number.
+/// * `area`: Memory area of the address, `DataBlock` when omitted.
+/// * `db`: **Data Block** (DB) number (ignored outside of `DataBlock`).
 /// * `byte`: Index of the byte within the DB.
 /// 
 /// # Exemple
 /// ```rust
-/// let addr = ByteAddress { db: 1, byte: 20 };
+/// use s7_device::types::{ByteAddress, MemoryArea};
+///
+/// let addr = ByteAddress { area: MemoryArea::DataBlock, db: 1, byte: 20 };
 /// /* Represents DB1.DBB20 in Siemens notation */
+/// let addr = ByteAddress { area: MemoryArea::Flag, db: 0, byte: 20 };
+/// /* Represents MW20 in Siemens notation */
 /// ```
 pub struct ByteAddress {
+    #[serde(default)]
+    pub area: MemoryArea,
     pub db: u16,
     pub byte: u16,
 }
 
+impl From<&BitAddress> for Address {
+    fn from(value: &BitAddress) -> Self {
+        Address {
+            area: value.area,
+            db: value.db,
+            byte: value.byte,
+            width: AddressWidth::Bit(value.bit),
+            pointer: None,
+        }
+    }
+}
+
+impl From<&ByteAddress> for Address {
+    fn from(value: &ByteAddress) -> Self {
+        Address {
+            area: value.area,
+            db: value.db,
+            byte: value.byte,
+            width: AddressWidth::Byte,
+            pointer: None,
+        }
+    }
+}
+
+impl fmt::Display for BitAddress {
+    /// Formats the address in the Siemens notation (`DB1.DBX10.3`, `Q4.1`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Address::from(self).fmt(f)
+    }
+}
+
+impl fmt::Display for ByteAddress {
+    /// Formats the address in the Siemens notation, as a byte (`DB1.DBB20`, `MB20`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Address::from(self).fmt(f)
+    }
+}
+
+impl fmt::Display for RegAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegAddress::Byte(addr) => addr.fmt(f),
+            RegAddress::Bit(addr) => addr.fmt(f),
+        }
+    }
+}
+
+impl FromStr for RegAddress {
+    type Err = AddressError;
+
+    /// Parses an address in the Siemens notation, the width of byte addresses is not kept.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(RegAddress::from(&address::parse(s)?))
+    }
+}
+
+impl FromStr for BitAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse()? {
+            RegAddress::Bit(addr) => Ok(addr),
+            RegAddress::Byte(_) => Err(AddressError::MismatchedWidth {
+                address: s.to_string(),
+                expected: "bit".to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for ByteAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse()? {
+            RegAddress::Byte(addr) => Ok(addr),
+            RegAddress::Bit(_) => Err(AddressError::MismatchedWidth {
+                address: s.to_string(),
+                expected: "byte".to_string(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<String> for RegAddress {
+    type Error = AddressError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RegAddress> for String {
+    fn from(value: RegAddress) -> Self {
+        value.to_string()
+    }
+}
+
 impl From<ByteAddress> for RegAddress {
     fn from(value: ByteAddress) -> Self {
         RegAddress::Byte(value)
@@ -137,7 +640,7 @@ impl TryFrom<RegAddress> for ByteAddress {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// Format of a register
 /// 
 /// Parameters :
@@ -145,21 +648,889 @@ impl TryFrom<RegAddress> for ByteAddress {
 /// * `data_type` (`DataType`) - the type of the data of the register
 /// * `name` (`String`) - the name of the register
 /// * `addr` (`RegAddress`) - the adresse of the register
+/// * `length` (`Option<u16>`) - the capacity of a `STRING`/`WSTRING` register, `DEFAULT_STRING_LENGTH` when `None`
+/// * `array` (`Option<ArrayBounds>`) - the bounds of an `ARRAY[lower..upper] OF data_type` register
+/// * `fields` (`Vec<Register>`) - the fields of a `STRUCT` register, their addresses are relative to
+///   the start of the struct (`DB0.DBX4.0` is the bit `0` of the byte `4` of the struct)
+/// * `scaling` (`Option<Scaling>`) - the conversion of the raw value to an engineering value
+/// * `unit` (`Option<String>`) - the unit of the engineering value (`"°C"`, `"bar"`...)
+/// * `description` (`Option<String>`) - a human readable description of the register
+/// * `access` (`Access`) - whether the register can be read and/or written, `ReadWrite` by default
+/// * `min`, `max` (`Option<f64>`) - the limits of the (engineering) value of the register
+/// * `tags` (`Vec<String>`) - free-form tags to classify the registers
+/// * `byte_order` (`ByteOrder`) - the order of the bytes of numeric values, `ABCD` by default
+/// * `initial` (`Option<RegisterValue>`) - the start value declared by the block source the
+///   register was imported from (see `import`), held by the top level registers only
 /// 
 /// ```
 pub struct Register {
     pub data_type: DataType,
     pub name: String,
     pub addr: RegAddress,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array: Option<ArrayBounds>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Register>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scaling: Option<Scaling>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Access::is_read_write")]
+    pub access: Access,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "ByteOrder::is_big_endian")]
+    pub byte_order: ByteOrder,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<RegisterValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Order of the bytes of a numeric value in the PLC memory, `A` being the most significant byte.
+///
+/// S7 PLCs store values in big endian (`ABCD`), the other orders are found in data mirrored
+/// from other devices (e.g. through Modbus gateways). For 64 bits values the pattern is
+/// extended to the 4 words (`CDAB` swaps the order of the words, `BADC` the bytes of each word).
+///
+/// Variants :
+/// * `ABCD` - big endian, the default.
+/// * `DCBA` - little endian.
+/// * `BADC` - big endian words with their bytes swapped.
+/// * `CDAB` - little endian words with big endian bytes (word swap).
+pub enum ByteOrder {
+    #[default]
+    ABCD,
+    DCBA,
+    BADC,
+    CDAB,
+}
+
+impl ByteOrder {
+    /// Reorders bytes between this order and big endian, in both directions as every
+    /// reordering is its own inverse.
+    pub fn swap(&self, mut bytes: Vec<u8>) -> Vec<u8> {
+        match self {
+            ByteOrder::ABCD => {}
+            ByteOrder::DCBA => bytes.reverse(),
+            ByteOrder::BADC => bytes.chunks_exact_mut(2).for_each(|word| word.swap(0, 1)),
+            ByteOrder::CDAB => {
+                bytes.reverse();
+                bytes.chunks_exact_mut(2).for_each(|word| word.swap(0, 1));
+            }
+        }
+        bytes
+    }
+
+    pub(crate) fn is_big_endian(&self) -> bool {
+        *self == ByteOrder::ABCD
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// What to do when a value written to a register is out of its `min`/`max` limits.
+///
+/// Variants :
+/// * `Reject` - the write fails with an `OutOfLimitsError`, the default.
+/// * `Clamp` - the value is replaced by the nearest limit.
+pub enum LimitPolicy {
+    #[default]
+    Reject,
+    Clamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Access mode of a register.
+///
+/// Variants :
+/// * `ReadOnly` - `"RO"` (or `"read-only"`) in the definition files.
+/// * `ReadWrite` - `"RW"` (or `"read-write"`), the default.
+/// * `WriteOnly` - `"WO"` (or `"write-only"`).
+pub enum Access {
+    #[serde(rename = "RO", alias = "read-only")]
+    ReadOnly,
+    #[default]
+    #[serde(rename = "RW", alias = "read-write")]
+    ReadWrite,
+    #[serde(rename = "WO", alias = "write-only")]
+    WriteOnly,
+}
+
+impl Access {
+    /// Whether the register can be read.
+    pub fn is_readable(&self) -> bool {
+        *self != Access::WriteOnly
+    }
+
+    /// Whether the register can be written.
+    pub fn is_writable(&self) -> bool {
+        *self != Access::ReadOnly
+    }
+
+    pub(crate) fn is_read_write(&self) -> bool {
+        *self == Access::ReadWrite
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "(i32, i32)", into = "(i32, i32)")]
+/// Bounds of an `ARRAY[lower..upper]`, both included.
+///
+/// In the definition files the bounds are written as a pair : `"array": [0, 9]`.
+pub struct ArrayBounds {
+    pub lower: i32,
+    pub upper: i32,
+}
+
+impl ArrayBounds {
+    /// Number of elements in the array.
+    pub fn count(&self) -> usize {
+        (i64::from(self.upper) - i64::from(self.lower) + 1).max(0) as usize
+    }
+
+    /// Position of the element `index` from the start of the array, `None` when out of bounds.
+    pub fn position(&self, index: i32) -> Option<usize> {
+        if index < self.lower || index > self.upper {
+            return None;
+        }
+        Some((i64::from(index) - i64::from(self.lower)) as usize)
+    }
+}
+
+impl From<(i32, i32)> for ArrayBounds {
+    fn from((lower, upper): (i32, i32)) -> Self {
+        ArrayBounds { lower, upper }
+    }
+}
+
+impl From<ArrayBounds> for (i32, i32) {
+    fn from(value: ArrayBounds) -> Self {
+        (value.lower, value.upper)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+/// Linear conversion between the raw value of a register and its engineering value.
+///
+/// In the definition files the scaling is written either with ranges,
+/// `"scaling": {"raw": [0, 27648], "eng": [0.0, 100.0]}`, or with a factor and an offset,
+/// `"scaling": {"factor": 0.1, "offset": -50.0}`.
+pub enum Scaling {
+    /// Maps the `raw` range onto the `eng` range, values outside of the ranges are extrapolated.
+    Range { raw: (f64, f64), eng: (f64, f64) },
+    /// `engineering = raw * factor + offset`
+    Linear {
+        #[serde(default = "Scaling::default_factor")]
+        factor: f64,
+        #[serde(default)]
+        offset: f64,
+    },
+}
+
+impl Scaling {
+    fn default_factor() -> f64 {
+        1.0
+    }
+
+    /// Factor and offset of the conversion from the raw value to the engineering value.
+    pub fn factor_offset(&self) -> (f64, f64) {
+        match *self {
+            Scaling::Range { raw, eng } => {
+                let factor = (eng.1 - eng.0) / (raw.1 - raw.0);
+                (factor, eng.0 - raw.0 * factor)
+            }
+            Scaling::Linear { factor, offset } => (factor, offset),
+        }
+    }
+
+    /// Converts a raw value to its engineering value.
+    ///
+    /// Errors :
+    /// - `InvalidRegisterValue` if the scaling is degenerated (e.g. an empty raw range).
+    pub fn to_engineering(&self, raw: f64) -> Result<f64, S7Error> {
+        let (factor, offset) = self.factor_offset();
+        let res = raw * factor + offset;
+        if !res.is_finite() {
+            return Err(S7Error::InvalidRegisterValue);
+        }
+        Ok(res)
+    }
+
+    /// Converts an engineering value back to its raw value.
+    ///
+    /// Errors :
+    /// - `InvalidRegisterValue` if the scaling cannot be inverted (e.g. a factor of `0`).
+    pub fn to_raw(&self, eng: f64) -> Result<f64, S7Error> {
+        let (factor, offset) = self.factor_offset();
+        let res = (eng - offset) / factor;
+        if !res.is_finite() {
+            return Err(S7Error::InvalidRegisterValue);
+        }
+        Ok(res)
+    }
+}
+
+/// Builds the value of a numeric `data_type` from a float, rounding it to the nearest integer
+/// for integer types.
+///
+/// Errors :
+/// - `InvalidRegisterValue` if the value is out of the range of the type or the type is not numeric.
+pub(crate) fn from_f64(data_type: DataType, val: f64) -> Result<RegisterValue, S7Error> {
+    let int = val.round();
+    let in_range = |min: f64, max: f64| {
+        if int < min || int > max {
+            return Err(S7Error::InvalidRegisterValue);
+        }
+        Ok(int)
+    };
+    let res = match data_type {
+        DataType::BYTE | DataType::USINT => RegisterValue::U8(in_range(0.0, u8::MAX.into())? as u8),
+        DataType::SINT => RegisterValue::S8(in_range(i8::MIN.into(), i8::MAX.into())? as i8),
+        DataType::WORD | DataType::UINT => {
+            RegisterValue::U16(in_range(0.0, u16::MAX.into())? as u16)
+        }
+        DataType::INT16 => RegisterValue::S16(in_range(i16::MIN.into(), i16::MAX.into())? as i16),
+        DataType::DWORD | DataType::UDINT => {
+            RegisterValue::U32(in_range(0.0, u32::MAX.into())? as u32)
+        }
+        DataType::INT32 => RegisterValue::S32(in_range(i32::MIN.into(), i32::MAX.into())? as i32),
+        DataType::LWORD | DataType::ULINT => {
+            RegisterValue::U64(in_range(0.0, u64::MAX as f64)? as u64)
+        }
+        DataType::LINT => RegisterValue::S64(in_range(i64::MIN as f64, i64::MAX as f64)? as i64),
+        DataType::FLOAT => RegisterValue::Float32(val as f32),
+        DataType::LREAL => RegisterValue::Float64(val),
+        _ => return Err(S7Error::InvalidRegisterValue),
+    };
+    Ok(res)
+}
+
+impl Register {
+    /// Creates a register without any optional property.
+    ///
+    /// Parameters :
+    /// * `name`: the name of the register.
+    /// * `data_type`: the type of the data of the register.
+    /// * `addr`: the address of the register.
+    pub fn new(name: impl Into<String>, data_type: DataType, addr: RegAddress) -> Self {
+        Register {
+            data_type,
+            name: name.into(),
+            addr,
+            length: None,
+            array: None,
+            fields: Vec::new(),
+            scaling: None,
+            unit: None,
+            description: None,
+            access: Access::ReadWrite,
+            min: None,
+            max: None,
+            tags: Vec::new(),
+            byte_order: ByteOrder::ABCD,
+            initial: None,
+        }
+    }
+
+    /// Memory area of the register.
+    pub fn area(&self) -> MemoryArea {
+        match &self.addr {
+            RegAddress::Byte(addr) => addr.area,
+            RegAddress::Bit(addr) => addr.area,
+        }
+    }
+
+    /// Offset in bytes of the register from the start of the memory area
+    /// (from the start of the parent struct for a struct field).
+    pub fn byte_offset(&self) -> u16 {
+        match &self.addr {
+            RegAddress::Byte(addr) => addr.byte,
+            RegAddress::Bit(addr) => addr.byte,
+        }
+    }
+
+    /// Capacity in characters of a string register.
+    pub fn string_length(&self) -> u16 {
+        self.length.unwrap_or(DEFAULT_STRING_LENGTH)
+    }
+
+    /// Size in bytes of one element of the register (the whole register when it is not an array).
+    ///
+    /// Structs span up to the end of their last field and are padded to an even size, as in
+    /// non-optimized blocks. The size is saturated at `u16::MAX`, see `checked_element_size`.
+    pub fn element_size(&self) -> u16 {
+        self.checked_element_size().unwrap_or(u16::MAX)
+    }
+
+    /// Size in bytes of one element of the register, `None` if it exceeds the 65535 bytes
+    /// that can be addressed in a memory area.
+    pub fn checked_element_size(&self) -> Option<u16> {
+        match self.data_type {
+            DataType::STRUCT => {
+                let mut end: u16 = 0;
+                for field in &self.fields {
+                    end = end.max(field.byte_offset().checked_add(field.checked_size()?)?);
+                }
+                end.checked_add(end % 2)
+            }
+            DataType::STRING => u16::try_from(u32::from(self.string_length()) + 2).ok(),
+            DataType::WSTRING => u16::try_from(2 * u32::from(self.string_length()) + 4).ok(),
+            data_type => Some(data_type.size()),
+        }
+    }
+
+    /// Size in bytes of the register in the PLC memory.
+    ///
+    /// Arrays of `BOOL` are packed, the size covers the bytes holding the bits of the array.
+    /// The size is saturated at `u16::MAX`, see `checked_size`.
+    pub fn size(&self) -> u16 {
+        self.checked_size().unwrap_or(u16::MAX)
+    }
+
+    /// Size in bytes of the register in the PLC memory, `None` if it exceeds the 65535 bytes
+    /// that can be addressed in a memory area.
+    pub fn checked_size(&self) -> Option<u16> {
+        match (&self.array, &self.addr) {
+            (None, _) => self.checked_element_size(),
+            (Some(bounds), RegAddress::Bit(addr)) => {
+                u16::try_from((usize::from(addr.bit) + bounds.count()).div_ceil(8)).ok()
+            }
+            (Some(bounds), RegAddress::Byte(_)) => {
+                let element_size = usize::from(self.checked_element_size()?);
+                u16::try_from(bounds.count().checked_mul(element_size)?).ok()
+            }
+        }
+    }
+
+    /// Builds the register of the element `index` of an array register.
+    ///
+    /// The element is named `Name[index]` and its initial value is the element of the initial
+    /// value of the array.
+    ///
+    /// Errors :
+    /// - `RegisterDoesNotExistsError` if the register is not an array or if `index` is out of
+    ///   bounds.
+    /// - `AddressOverflowError` if the element starts past the 65535 bytes of the memory area.
+    pub fn element(&self, index: i32) -> Result<Register, S7Error> {
+        let position = self
+            .array
+            .and_then(|bounds| bounds.position(index))
+            .ok_or(S7Error::RegisterDoesNotExistsError)?;
+        let name = format!("{}[{index}]", self.name);
+        let overflow = || S7Error::AddressOverflowError { name: name.clone() };
+        let addr = match &self.addr {
+            RegAddress::Bit(addr) => {
+                let bit = usize::from(addr.bit) + position;
+                let offset = u16::try_from(bit / 8).map_err(|_| overflow())?;
+                RegAddress::Bit(BitAddress {
+                    byte: addr.byte.checked_add(offset).ok_or_else(overflow)?,
+                    bit: (bit % 8) as u8,
+                    ..addr.clone()
+                })
+            }
+            RegAddress::Byte(addr) => {
+                let offset = position
+                    .checked_mul(usize::from(self.element_size()))
+                    .and_then(|offset| u16::try_from(offset).ok())
+                    .ok_or_else(overflow)?;
+                RegAddress::Byte(ByteAddress {
+                    byte: addr.byte.checked_add(offset).ok_or_else(overflow)?,
+                    ..addr.clone()
+                })
+            }
+        };
+        let initial = match &self.initial {
+            Some(RegisterValue::Array(vals)) => vals.get(position).cloned(),
+            _ => None,
+        };
+        Ok(Register {
+            name,
+            addr,
+            array: None,
+            initial,
+            ..self.clone()
+        })
+    }
+
+    /// Builds the register of the field `name` of a struct register, at its absolute address.
+    ///
+    /// The field is named `Name.field` and its initial value is the field of the initial value
+    /// of the struct.
+    ///
+    /// Errors :
+    /// - `RegisterDoesNotExistsError` if the register has no such field or is an array (access
+    ///   an element first).
+    /// - `AddressOverflowError` if the field starts past the 65535 bytes of the memory area.
+    pub fn field(&self, name: &str) -> Result<Register, S7Error> {
+        if self.array.is_some() {
+            return Err(S7Error::RegisterDoesNotExistsError);
+        }
+        let field = self
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or(S7Error::RegisterDoesNotExistsError)?;
+        let full_name = format!("{}.{name}", self.name);
+        let (area, db, base) = match &self.addr {
+            RegAddress::Byte(addr) => (addr.area, addr.db, addr.byte),
+            RegAddress::Bit(addr) => (addr.area, addr.db, addr.byte),
+        };
+        let byte =
+            base.checked_add(field.byte_offset())
+                .ok_or_else(|| S7Error::AddressOverflowError {
+                    name: full_name.clone(),
+                })?;
+        let addr = match &field.addr {
+            RegAddress::Byte(_) => RegAddress::Byte(ByteAddress { area, db, byte }),
+            RegAddress::Bit(addr) => RegAddress::Bit(BitAddress {
+                area,
+                db,
+                byte,
+                bit: addr.bit,
+            }),
+        };
+        let initial = match &self.initial {
+            Some(RegisterValue::Struct(vals)) => vals.get(name).cloned(),
+            _ => None,
+        };
+        Ok(Register {
+            name: full_name,
+            addr,
+            initial,
+            ..field.clone()
+        })
+    }
+
+    /// Whether writing the register requires the bytes currently held by the PLC,
+    /// i.e. for `BOOL` arrays and structs (see `merge`).
+    pub fn needs_merge(&self) -> bool {
+        self.data_type == DataType::STRUCT
+            || (self.data_type == DataType::BOOL && self.array.is_some())
+    }
+
+    /// Writes a value into `current`, the bytes currently held by the register, and returns the
+    /// updated bytes.
+    ///
+    /// Only the bits of `BOOL` registers are changed and a `Struct` value only needs to contain
+    /// the fields to update, the other fields keep their current value.
+    ///
+    /// Errors :
+    /// - `MismatchedRegisterLengthError` if `current` does not have the size of the register.
+    /// - `RegisterDoesNotExistsError` if a `Struct` value contains an unknown field.
+    /// - `InvalidRegisterValue` if the value does not match the register.
+    pub fn merge(&self, value: RegisterValue, mut current: Vec<u8>) -> Result<Vec<u8>, S7Error> {
+        if current.len() != self.size() as usize {
+            return Err(S7Error::MismatchedRegisterLengthError);
+        }
+        if let Some(bounds) = self.array {
+            if self.data_type == DataType::BOOL {
+                return self.merge_bits(value, current);
+            }
+            let vals = match value {
+                RegisterValue::Array(vals) if vals.len() == bounds.count() => vals,
+                _ => return Err(S7Error::InvalidRegisterValue),
+            };
+            let element = Register {
+                array: None,
+                ..self.clone()
+            };
+            let element_size = self.element_size() as usize;
+            for (val, chunk) in vals.into_iter().zip(current.chunks_exact_mut(element_size)) {
+                let merged = element.merge(val, chunk.to_vec())?;
+                chunk.copy_from_slice(&merged);
+            }
+            return Ok(current);
+        }
+        match (self.data_type, value) {
+            (DataType::STRUCT, RegisterValue::Struct(vals)) => {
+                for (name, val) in vals {
+                    let field = self
+                        .fields
+                        .iter()
+                        .find(|field| field.name == name)
+                        .ok_or(S7Error::RegisterDoesNotExistsError)?;
+                    let start = field.byte_offset() as usize;
+                    let slice = current
+                        .get_mut(start..start + field.size() as usize)
+                        .ok_or(S7Error::MismatchedRegisterLengthError)?;
+                    let merged = field.merge(val, slice.to_vec())?;
+                    slice.copy_from_slice(&merged);
+                }
+                Ok(current)
+            }
+            (DataType::STRUCT, _) => Err(S7Error::InvalidRegisterValue),
+            (DataType::BOOL, value) => {
+                let bit = match &self.addr {
+                    RegAddress::Bit(addr) => addr.bit,
+                    RegAddress::Byte(_) => return Err(S7Error::MismatchedRegisterLengthError),
+                };
+                if bool::try_from(value)? {
+                    current[0] |= 1 << bit;
+                } else {
+                    current[0] &= !(1 << bit);
+                }
+                Ok(current)
+            }
+            (_, value) => {
+                let raw = self.encode(value)?;
+                current
+                    .get_mut(..raw.len())
+                    .ok_or(S7Error::MismatchedRegisterLengthError)?
+                    .copy_from_slice(&raw);
+                Ok(current)
+            }
+        }
+    }
+
+    /// Writes the bits of a `BOOL` array value into `current`, the bytes currently held by the
+    /// register, leaving the bits outside of the array untouched.
+    ///
+    /// Errors :
+    /// - `MismatchedRegisterLengthError` if the register is not a `BOOL` array or if `current`
+    ///   does not have the size of the register.
+    /// - `InvalidRegisterValue` if the value is not an array of booleans of the array size.
+    pub fn merge_bits(
+        &self,
+        value: RegisterValue,
+        mut current: Vec<u8>,
+    ) -> Result<Vec<u8>, S7Error> {
+        let (bounds, addr) = match (&self.data_type, &self.array, &self.addr) {
+            (DataType::BOOL, Some(bounds), RegAddress::Bit(addr)) => (bounds, addr),
+            _ => return Err(S7Error::MismatchedRegisterLengthError),
+        };
+        if current.len() != self.size() as usize {
+            return Err(S7Error::MismatchedRegisterLengthError);
+        }
+        let vals = match value {
+            RegisterValue::Array(vals) if vals.len() == bounds.count() => vals,
+            _ => return Err(S7Error::InvalidRegisterValue),
+        };
+        for (i, val) in vals.into_iter().enumerate() {
+            let bit = usize::from(addr.bit) + i;
+            if bool::try_from(val)? {
+                current[bit / 8] |= 1 << (bit % 8);
+            } else {
+                current[bit / 8] &= !(1 << (bit % 8));
+            }
+        }
+        Ok(current)
+    }
+
+    /// Converts a raw value read from the register to its engineering value.
+    ///
+    /// Numeric values of a register with a `scaling` become a `Float64`, the elements of arrays
+    /// and the fields of structs are converted with the scaling of the register and of each
+    /// field. Values without scaling are returned unchanged.
+    ///
+    /// Errors :
+    /// - `InvalidRegisterValue` if a scaled value is not numeric or the scaling is degenerated.
+    pub fn scale(&self, value: RegisterValue) -> Result<RegisterValue, S7Error> {
+        match value {
+            RegisterValue::Array(vals) => Ok(RegisterValue::Array(
+                vals.into_iter()
+                    .map(|val| self.scale(val))
+                    .collect::<Result<_, _>>()?,
+            )),
+            RegisterValue::Struct(vals) => Ok(RegisterValue::Struct(
+                vals.into_iter()
+                    .map(
+                        |(name, val)| match self.fields.iter().find(|f| f.name == name) {
+                            Some(field) => Ok((name, field.scale(val)?)),
+                            None => Ok((name, val)),
+                        },
+                    )
+                    .collect::<Result<_, S7Error>>()?,
+            )),
+            value => match &self.scaling {
+                Some(scaling) => Ok(RegisterValue::Float64(
+                    scaling.to_engineering(value.try_into()?)?,
+                )),
+                None => Ok(value),
+            },
+        }
+    }
+
+    /// Converts an engineering value back to the raw value of the register, the inverse of `scale`.
+    ///
+    /// Any numeric value is accepted for a register with a `scaling`, the raw value is rounded
+    /// to the nearest integer for integer types.
+    ///
+    /// Errors :
+    /// - `RegisterDoesNotExistsError` if a `Struct` value contains an unknown field.
+    /// - `InvalidRegisterValue` if the value is not numeric or the raw value is out of the range
+    ///   of the data type.
+    pub fn unscale(&self, value: RegisterValue) -> Result<RegisterValue, S7Error> {
+        match value {
+            RegisterValue::Array(vals) => Ok(RegisterValue::Array(
+                vals.into_iter()
+                    .map(|val| self.unscale(val))
+                    .collect::<Result<_, _>>()?,
+            )),
+            RegisterValue::Struct(vals) => Ok(RegisterValue::Struct(
+                vals.into_iter()
+                    .map(|(name, val)| {
+                        let field = self
+                            .fields
+                            .iter()
+                            .find(|f| f.name == name)
+                            .ok_or(S7Error::RegisterDoesNotExistsError)?;
+                        Ok((name, field.unscale(val)?))
+                    })
+                    .collect::<Result<_, S7Error>>()?,
+            )),
+            value => match &self.scaling {
+                Some(scaling) => from_f64(self.data_type, scaling.to_raw(value.try_into()?)?),
+                None => Ok(value),
+            },
+        }
+    }
+
+    /// Checks that a value can be written to the register according to its access mode.
+    ///
+    /// The fields of a struct and the elements of an array are checked against their own
+    /// access mode, only for the fields present in the value.
+    ///
+    /// Errors :
+    /// - `ReadOnlyRegisterError` if the register or one of the written fields is read-only.
+    /// - `RegisterDoesNotExistsError` if a `Struct` value contains an unknown field.
+    /// - `AddressOverflowError` if an element or a field is past the end of the memory area.
+    pub fn check_access(&self, value: &RegisterValue) -> Result<(), S7Error> {
+        if !self.access.is_writable() {
+            return Err(S7Error::ReadOnlyRegisterError {
+                name: self.name.clone(),
+            });
+        }
+        match (value, &self.array) {
+            (RegisterValue::Array(vals), Some(bounds)) => {
+                for (index, val) in (bounds.lower..).zip(vals) {
+                    let element = self.element(index)?;
+                    element.check_access(val)?;
+                }
+                Ok(())
+            }
+            (RegisterValue::Struct(vals), None) => {
+                for (name, val) in vals {
+                    let field = self.field(name)?;
+                    field.check_access(val)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the `min`/`max` limits of the register (and of its fields) to a value to write.
+    ///
+    /// The limits are compared to the engineering value, before the scaling is inverted.
+    /// Values without limits and values that are not numeric are returned unchanged.
+    ///
+    /// Errors :
+    /// - `OutOfLimitsError` if the value is out of the limits with the `Reject` policy.
+    /// - `RegisterDoesNotExistsError` if a `Struct` value contains an unknown field.
+    /// - `AddressOverflowError` if an element or a field is past the end of the memory area.
+    pub fn apply_limits(
+        &self,
+        value: RegisterValue,
+        policy: LimitPolicy,
+    ) -> Result<RegisterValue, S7Error> {
+        match (value, &self.array) {
+            (RegisterValue::Array(vals), Some(bounds)) => Ok(RegisterValue::Array(
+                (bounds.lower..)
+                    .zip(vals)
+                    .map(|(index, val)| {
+                        let element = self.element(index)?;
+                        element.apply_limits(val, policy)
+                    })
+                    .collect::<Result<_, _>>()?,
+            )),
+            (RegisterValue::Struct(vals), None) => Ok(RegisterValue::Struct(
+                vals.into_iter()
+                    .map(|(name, val)| {
+                        let field = self.field(&name)?;
+                        Ok((name, field.apply_limits(val, policy)?))
+                    })
+                    .collect::<Result<_, S7Error>>()?,
+            )),
+            (value, _) => {
+                if self.min.is_none() && self.max.is_none() {
+                    return Ok(value);
+                }
+                let Ok(val) = f64::try_from(value.clone()) else {
+                    return Ok(value);
+                };
+                let min = self.min.unwrap_or(f64::NEG_INFINITY);
+                let max = self.max.unwrap_or(f64::INFINITY);
+                if min > max {
+                    return Err(S7Error::InvalidRegisterValue);
+                }
+                if (min..=max).contains(&val) {
+                    return Ok(value);
+                }
+                match policy {
+                    LimitPolicy::Reject => Err(S7Error::OutOfLimitsError {
+                        name: self.name.clone(),
+                        value: val,
+                    }),
+                    LimitPolicy::Clamp if self.scaling.is_some() => {
+                        Ok(RegisterValue::Float64(val.clamp(min, max)))
+                    }
+                    LimitPolicy::Clamp => from_f64(self.data_type, val.clamp(min, max)),
+                }
+            }
+        }
+    }
+
+    /// Encodes a value to the raw bytes of this register.
+    ///
+    /// Strings are written with their header, using the capacity declared on the register.
+    /// Arrays and structs are encoded element by element and field by field, the bytes and bits
+    /// not covered by the value are set to `0` (see `merge` to keep them).
+    ///
+    /// Errors :
+    /// - `StringTooLongError` if the string does not fit in the register capacity.
+    /// - `InvalidStringLength` if the capacity exceeds the maximum of the string type.
+    /// - `InvalidRegisterValue` if the value does not match the register type.
+    pub fn encode(&self, value: RegisterValue) -> Result<Vec<u8>, S7Error> {
+        if self.array.is_some() || self.data_type == DataType::STRUCT {
+            return self.merge(value, vec![0; self.size() as usize]);
+        }
+        let capacity = self.string_length();
+        if let Some(max) = self.data_type.max_length().filter(|max| capacity > *max) {
+            return Err(S7Error::InvalidStringLength { capacity, max });
+        }
+        match (self.data_type, value) {
+            (DataType::STRING, RegisterValue::String(val)) => {
+                let chars: Vec<u8> = val
+                    .chars()
+                    .map(|c| u8::try_from(c).map_err(|_| S7Error::InvalidRegisterValue))
+                    .collect::<Result<_, _>>()?;
+                if chars.len() > capacity as usize {
+                    return Err(S7Error::StringTooLongError {
+                        len: chars.len(),
+                        capacity,
+                    });
+                }
+                let mut res = vec![capacity as u8, chars.len() as u8];
+                res.extend(chars);
+                Ok(res)
+            }
+            (DataType::WSTRING, RegisterValue::String(val)) => {
+                let chars: Vec<u16> = val.encode_utf16().collect();
+                if chars.len() > capacity as usize {
+                    return Err(S7Error::StringTooLongError {
+                        len: chars.len(),
+                        capacity,
+                    });
+                }
+                let mut res = Vec::with_capacity(4 + 2 * chars.len());
+                res.extend(capacity.to_be_bytes());
+                res.extend((chars.len() as u16).to_be_bytes());
+                res.extend(chars.iter().flat_map(|c| c.to_be_bytes()));
+                Ok(res)
+            }
+            (DataType::STRING | DataType::WSTRING, _) | (_, RegisterValue::String(_)) => {
+                Err(S7Error::InvalidRegisterValue)
+            }
+            (data_type, value) if data_type.is_time() => datetime::encode(data_type, &value),
+            (data_type, value) if data_type.is_numeric() => {
+                Ok(self.byte_order.swap(value.try_into()?))
+            }
+            (_, value) => value.try_into(),
+        }
+    }
+}
+
+/// Converts the raw bytes read from the PLC into a fixed size array
+///
+/// Errors :
+/// - `MismatchedRegisterLengthError` if the amount of bytes does not match `N`.
+fn to_array<const N: usize>(raw: Vec<u8>) -> Result<[u8; N], S7Error> {
+    raw.try_into()
+        .map_err(|_err| S7Error::MismatchedRegisterLengthError)
+}
+
+/// Splits the header of a string from its characters
+///
+/// Errors :
+/// - `MismatchedRegisterLengthError` if the raw data is shorter than the header.
+fn split_header(raw: &[u8], header_len: usize) -> Result<(&[u8], &[u8]), S7Error> {
+    if raw.len() < header_len {
+        return Err(S7Error::MismatchedRegisterLengthError);
+    }
+    Ok(raw.split_at(header_len))
+}
+
+/// Converts the raw bytes of an array register into an `Array` of its elements
+///
+/// Errors :
+/// - `MismatchedRegisterLengthError` if the amount of bytes does not match the register size.
+fn decode_array(
+    raw: Vec<u8>,
+    reg: Register,
+    bounds: ArrayBounds,
+) -> Result<RegisterValue, S7Error> {
+    if raw.len() != reg.size() as usize {
+        return Err(S7Error::MismatchedRegisterLengthError);
+    }
+    if let RegAddress::Bit(addr) = &reg.addr {
+        let vals = (0..bounds.count())
+            .map(|i| {
+                let bit = usize::from(addr.bit) + i;
+                RegisterValue::Boolean(raw[bit / 8] & (1 << (bit % 8)) != 0)
+            })
+            .collect();
+        return Ok(RegisterValue::Array(vals));
+    }
+    let element = Register { array: None, ..reg };
+    let vals = raw
+        .chunks_exact(element.element_size() as usize)
+        .map(|chunk| (chunk.to_vec(), element.clone()).try_into())
+        .collect::<Result<_, _>>()?;
+    Ok(RegisterValue::Array(vals))
+}
+
+/// Converts the raw bytes of a struct register into a `Struct` of its fields
+///
+/// Errors :
+/// - `MismatchedRegisterLengthError` if the amount of bytes does not match the register size.
+fn decode_struct(raw: Vec<u8>, reg: Register) -> Result<RegisterValue, S7Error> {
+    if raw.len() != reg.size() as usize {
+        return Err(S7Error::MismatchedRegisterLengthError);
+    }
+    let mut vals = HashMap::with_capacity(reg.fields.len());
+    for field in reg.fields {
+        let start = field.byte_offset() as usize;
+        let chunk = raw
+            .get(start..start + field.size() as usize)
+            .ok_or(S7Error::MismatchedRegisterLengthError)?;
+        let name = field.name.clone();
+        vals.insert(name, (chunk.to_vec(), field).try_into()?);
+    }
+    Ok(RegisterValue::Struct(vals))
 }
 
 impl TryFrom<(Vec<u8>, Register)> for RegisterValue {
     type Error = S7Error;
 
     fn try_from((raw, datatype): (Vec<u8>, Register)) -> Result<Self, Self::Error> {
+        if let Some(bounds) = datatype.array {
+            return decode_array(raw, datatype, bounds);
+        }
+        let raw = match datatype.data_type.is_numeric() {
+            true => datatype.byte_order.swap(raw),
+            false => raw,
+        };
         match datatype.data_type {
             DataType::BOOL => {
-                let byte = raw.get(0);
+                let byte = raw.first();
                 if byte.is_none() {
                     return Err(S7Error::MismatchedRegisterLengthError);
                 }
@@ -170,27 +1541,53 @@ impl TryFrom<(Vec<u8>, Register)> for RegisterValue {
                 let bit = byte.ok_or(S7Error::MismatchedRegisterLengthError)? & (1 << addr.bit);
                 Ok(RegisterValue::Boolean(bit != 0))
             }
-            DataType::FLOAT => {
-                let val = f32::from_be_bytes(match raw.try_into() {
-                    Ok(val) => val,
-                    Err(_err) => return Err(S7Error::MismatchedRegisterLengthError),
-                });
-                Ok(RegisterValue::Float32(val))
-            }
-            DataType::INT32 => {
-                let val = i32::from_be_bytes(match raw.try_into() {
-                    Ok(val) => val,
-                    Err(_err) => return Err(S7Error::MismatchedRegisterLengthError),
-                });
-                Ok(RegisterValue::S32(val))
-            }
-            DataType::INT16 => {
-                let val = i16::from_be_bytes(match raw.try_into() {
-                    Ok(val) => val,
-                    Err(_err) => return Err(S7Error::MismatchedRegisterLengthError),
-                });
-                Ok(RegisterValue::S16(val))
+            DataType::BYTE | DataType::USINT => {
+                Ok(RegisterValue::U8(u8::from_be_bytes(to_array(raw)?)))
+            }
+            DataType::SINT => Ok(RegisterValue::S8(i8::from_be_bytes(to_array(raw)?))),
+            DataType::CHAR => {
+                let val = u8::from_be_bytes(to_array(raw)?);
+                Ok(RegisterValue::Char(val.into()))
+            }
+            DataType::WORD | DataType::UINT => {
+                Ok(RegisterValue::U16(u16::from_be_bytes(to_array(raw)?)))
+            }
+            DataType::INT16 => Ok(RegisterValue::S16(i16::from_be_bytes(to_array(raw)?))),
+            DataType::DWORD | DataType::UDINT => {
+                Ok(RegisterValue::U32(u32::from_be_bytes(to_array(raw)?)))
+            }
+            DataType::INT32 => Ok(RegisterValue::S32(i32::from_be_bytes(to_array(raw)?))),
+            DataType::FLOAT => Ok(RegisterValue::Float32(f32::from_be_bytes(to_array(raw)?))),
+            DataType::LWORD | DataType::ULINT => {
+                Ok(RegisterValue::U64(u64::from_be_bytes(to_array(raw)?)))
+            }
+            DataType::LINT => Ok(RegisterValue::S64(i64::from_be_bytes(to_array(raw)?))),
+            DataType::LREAL => Ok(RegisterValue::Float64(f64::from_be_bytes(to_array(raw)?))),
+            DataType::STRING => {
+                let (header, chars) = split_header(&raw, 2)?;
+                let len = (header[1] as usize).min(chars.len());
+                Ok(RegisterValue::String(
+                    chars[..len].iter().map(|c| char::from(*c)).collect(),
+                ))
+            }
+            DataType::WSTRING => {
+                let (header, chars) = split_header(&raw, 4)?;
+                let len =
+                    (u16::from_be_bytes([header[2], header[3]]) as usize).min(chars.len() / 2);
+                let chars: Vec<u16> = chars[..2 * len]
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                Ok(RegisterValue::String(String::from_utf16_lossy(&chars)))
             }
+            DataType::DATE
+            | DataType::TIME
+            | DataType::TOD
+            | DataType::DT
+            | DataType::DTL
+            | DataType::S5TIME
+            | DataType::LTIME => datetime::decode(datatype.data_type, &raw),
+            DataType::STRUCT => decode_struct(raw, datatype),
         }
     }
 }
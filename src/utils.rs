@@ -1,10 +1,19 @@
-use std::{collections::HashMap, fs::File};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::File,
+    io::Read,
+};
 
 use custom_error::custom_error;
-use de_regex;
+use encoding_rs::WINDOWS_1252;
 use serde::{Deserialize, Serialize};
 
-use crate::types::{BitAddress, ByteAddress, DataType, RegAddress, Register};
+use crate::address::{self, Address, AddressError, AddressWidth};
+use crate::namespace::SEPARATOR;
+use crate::types::{
+    Access, ArrayBounds, BitAddress, ByteAddress, ByteOrder, DataType, MemoryArea, RegAddress,
+    Register, Scaling,
+};
 
 #[derive(Serialize, Deserialize)]
 
@@ -14,72 +23,719 @@ use crate::types::{BitAddress, ByteAddress, DataType, RegAddress, Register};
 /// 
 /// * `type` (`DataType`) - the type of the data of the register
 /// * `name` (`String`) - the name of the register
-/// * `id` (`String`) - the adresse of the register
+/// * `id` (`String`) - the adresse of the register (`DB1.DBD4`, `%MW20`, `I0.0`, `P#DB1.DBX0.0 INT 4`...),
+///   see `address::parse`, the peripheral I/O (`PEW256`) is rejected
+/// * `length` (`Option<u16>`) - the capacity of a `STRING`/`WSTRING` register
+/// * `array` (`Option<ArrayBounds>`) - the bounds of an array register, e.g. `[0, 9]`
+/// * `struct` (`Option<String>`) - the struct type of a `STRUCT` register, declared in the `types` of the file
+/// * `scaling` (`Option<Scaling>`) - the conversion to an engineering value, `{"raw": [0, 27648], "eng": [0, 100]}`
+///   or `{"factor": 0.1, "offset": 0}`
+/// * `unit` (`Option<String>`) - the unit of the engineering value
+/// * `description` (`Option<String>`) - a human readable description
+/// * `access` (`Access`) - `"RO"`, `"RW"` (default) or `"WO"`
+/// * `min`, `max` (`Option<f64>`) - the limits of the (engineering) value
+/// * `tags` (`Vec<String>`) - free-form tags
+/// * `byte_order` (`ByteOrder`) - `"ABCD"` (default), `"DCBA"`, `"BADC"` or `"CDAB"`
 /// 
 /// ```
-struct RegistersFormat {
+pub(crate) struct RegistersFormat {
     pub name: String,
     pub id: String,
     #[serde(rename = "type")]
     pub type_: DataType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array: Option<ArrayBounds>,
+    #[serde(rename = "struct", default, skip_serializing_if = "Option::is_none")]
+    pub struct_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scaling: Option<Scaling>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Access::is_read_write")]
+    pub access: Access,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "ByteOrder::is_big_endian")]
+    pub byte_order: ByteOrder,
 }
 
-const BYTE_ADDRESS_REGEX: &str = r"^DB(?P<db>\d+)\.DBD(?P<byte>\d+)$";
-const WORD_ADDRESS_REGEX: &str = r"^DB(?P<db>\d+)\.DBW(?P<byte>\d+)$";
-const BIT_ADDRESS_REGEX: &str = r"^DB(?P<db>\d+)\.DBX(?P<byte>\d+)\.(?P<bit>\d+)$";
+#[derive(Serialize, Deserialize)]
+/// Format of a field of a struct type
+///
+/// Parameters :
+///
+/// * `name` (`String`) - the name of the field
+/// * `type` (`DataType`) - the type of the data of the field
+/// * `offset` (`u16`) - the offset in bytes of the field from the start of the struct
+/// * `bit` (`Option<u8>`) - the bit of a `BOOL` field in its byte, `0` when omitted
+/// * `length`, `array`, `struct`, `scaling`, `unit`, `description`, `access`, `min`, `max`,
+///   `tags`, `byte_order` - as for a register
+pub(crate) struct FieldFormat {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: DataType,
+    pub offset: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bit: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array: Option<ArrayBounds>,
+    #[serde(rename = "struct", default, skip_serializing_if = "Option::is_none")]
+    pub struct_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scaling: Option<Scaling>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Access::is_read_write")]
+    pub access: Access,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "ByteOrder::is_big_endian")]
+    pub byte_order: ByteOrder,
+}
 
-custom_error! {pub JsonReadError
-    SerdeJson{ err: serde_json::Error } = "Json parse error : {err}",
-    Regex{ err: de_regex::Error } = "Regex parsing error : {err},"
+#[derive(Serialize, Deserialize)]
+/// Format of a definition file declaring struct types
+///
+/// A definition file is either a plain list of registers or this document.
+///
+/// Parameters :
+///
+/// * `types` (`HashMap<String, Vec<FieldFormat>>`) - the struct types, by name
+/// * `registers` (`Vec<R>`) - the registers, kept as raw JSON values by the validation
+/// * `groups` (`HashMap<String, Vec<String>>`) - the names of the registers of each group, a
+///   group is added to the `tags` of its registers
+/// * `instances` (`Vec<InstanceFormat>`) - the instances of templates, expanded into registers
+pub(crate) struct DefinitionsFormat<R = RegistersFormat> {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub types: HashMap<String, Vec<FieldFormat>>,
+    #[serde(default = "Vec::new")]
+    pub registers: Vec<R>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub groups: HashMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instances: Vec<InstanceFormat>,
+}
+
+#[derive(Serialize, Deserialize)]
+/// Format of an instance of a template, for equipment repeated in several data blocks
+///
+/// A template is a struct type of the `types` of the file, each of its fields is expanded into
+/// a register named `prefix.field` at the offset of the field from the base of the instance.
+///
+/// ```json
+/// {
+///   "types": {
+///     "Valve": [
+///       { "name": "Open", "type": "BOOL", "offset": 0, "bit": 0 },
+///       { "name": "Position", "type": "REAL", "offset": 2, "unit": "%" }
+///     ]
+///   },
+///   "instances": [
+///     { "template": "Valve", "prefix": "Valve1", "db": 101 },
+///     { "template": "Valve", "prefix": "Valve2", "db": 102 }
+///   ]
+/// }
+/// ```
+///
+/// Parameters :
+///
+/// * `template` (`String`) - the struct type expanded by the instance
+/// * `prefix` (`String`) - the level added before the names of the fields
+/// * `db` (`u16`) - the data block of the instance
+/// * `offset` (`u16`) - the offset in bytes of the instance in its data block, `0` by default
+pub(crate) struct InstanceFormat {
+    pub template: String,
+    pub prefix: String,
+    pub db: u16,
+    #[serde(default)]
+    pub offset: u16,
 }
 
-impl From<de_regex::Error> for JsonReadError {
-    fn from(value: de_regex::Error) -> Self {
-        JsonReadError::Regex { err: value }
+impl<R: serde::de::DeserializeOwned> DefinitionsFormat<R> {
+    /// Reads a definition file, either a plain list of registers or a document with struct types.
+    pub(crate) fn from_value(raw: serde_json::Value) -> Result<Self, serde_json::Error> {
+        if raw.is_array() {
+            return Ok(DefinitionsFormat {
+                types: HashMap::new(),
+                registers: serde_json::from_value(raw)?,
+                groups: HashMap::new(),
+                instances: Vec::new(),
+            });
+        }
+        serde_json::from_value(raw)
     }
 }
+
+custom_error! {pub JsonReadError
+    SerdeJson{ err: serde_json::Error } = "Json parse error : {err}",
+    Address{ name: String, err: AddressError } = "Invalid address of the register {name} : {err}",
+    MismatchedAddress{ name: String, id: String } = "The address {id} does not match the type of the register {name}",
+    MismatchedWidth{ name: String, id: String, width: u16, size: u16 } = "The address {id} holds {width} bytes but the register {name} takes {size} bytes, use a byte address (DBB, MB...) to mark its start",
+    MismatchedPointer{ name: String, id: String } = "The pointer {id} does not match the type or the array bounds of the register {name}",
+    PeripheralArea{ name: String, id: String } = "The address {id} of the register {name} is in the peripheral I/O, which cannot be accessed, use the process image (I, Q) instead",
+    UnknownStructType{ name: String } = "The struct type {name} is not declared",
+    RecursiveStructType{ name: String } = "The struct type {name} contains itself",
+    MissingStructType{ name: String } = "The struct register {name} does not declare its struct type",
+    InvalidLength{ name: String, length: u16, max: u16 } = "The length {length} of the register {name} exceeds the maximum length {max} of its type",
+    InvalidFieldBit{ name: String, bit: u8 } = "The bit {bit} of the field {name} must be between 0 and 7",
+    InstanceOutOfRange{ prefix: String } = "The instance {prefix} exceeds the size of its data block",
+    DuplicateRegister{ name: String } = "The register {name} is defined several times",
+    UnknownGroupMember{ group: String, name: String } = "The register {name} of the group {group} is not defined"
+}
+
 impl From<serde_json::Error> for JsonReadError {
     fn from(value: serde_json::Error) -> Self {
         JsonReadError::SerdeJson { err: value }
     }
 }
+
+/// Builds the fields of the struct type `name`, with addresses relative to the start of the struct
+///
+/// `stack` holds the struct types being built, to detect types containing themselves.
+fn build_fields(
+    name: &str,
+    types: &HashMap<String, Vec<FieldFormat>>,
+    stack: &mut Vec<String>,
+) -> Result<Vec<Register>, JsonReadError> {
+    if stack.iter().any(|parent| parent == name) {
+        return Err(JsonReadError::RecursiveStructType {
+            name: name.to_string(),
+        });
+    }
+    let fields = types
+        .get(name)
+        .ok_or_else(|| JsonReadError::UnknownStructType {
+            name: name.to_string(),
+        })?;
+    stack.push(name.to_string());
+    let mut res = Vec::with_capacity(fields.len());
+    for f in fields {
+        if f.bit.is_some_and(|bit| bit > 7) {
+            return Err(JsonReadError::InvalidFieldBit {
+                name: f.name.clone(),
+                bit: f.bit.unwrap_or_default(),
+            });
+        }
+        check_length(&f.name, f.type_, f.length)?;
+        let addr: RegAddress = match f.type_ {
+            DataType::BOOL => BitAddress {
+                area: MemoryArea::DataBlock,
+                db: 0,
+                byte: f.offset,
+                bit: f.bit.unwrap_or_default(),
+            }
+            .into(),
+            _ => ByteAddress {
+                area: MemoryArea::DataBlock,
+                db: 0,
+                byte: f.offset,
+            }
+            .into(),
+        };
+        let mut reg = Register::new(f.name.clone(), f.type_, addr);
+        reg.length = f.length;
+        reg.array = f.array;
+        reg.scaling = f.scaling;
+        reg.unit = f.unit.clone();
+        reg.description = f.description.clone();
+        reg.access = f.access;
+        reg.min = f.min;
+        reg.max = f.max;
+        reg.tags = f.tags.clone();
+        reg.byte_order = f.byte_order;
+        reg.fields = struct_fields(&f.name, f.type_, f.struct_.as_deref(), types, stack)?;
+        res.push(reg);
+    }
+    stack.pop();
+    Ok(res)
+}
+
+/// Builds the fields of a register or field named `name`, empty unless it is a `STRUCT`
+fn struct_fields(
+    name: &str,
+    type_: DataType,
+    struct_: Option<&str>,
+    types: &HashMap<String, Vec<FieldFormat>>,
+    stack: &mut Vec<String>,
+) -> Result<Vec<Register>, JsonReadError> {
+    match (type_, struct_) {
+        (DataType::STRUCT, Some(struct_)) => build_fields(struct_, types, stack),
+        (DataType::STRUCT, None) => Err(JsonReadError::MissingStructType {
+            name: name.to_string(),
+        }),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Builds a register from its definition
+///
+/// Parameters :
+/// * `f`: the definition of the register.
+/// * `types`: the struct types declared in the definition file.
+///
+/// Errors :
+/// - `Address`, `MismatchedAddress`, `MismatchedWidth` if the address is invalid or does not
+///   match the type, see `check_width`.
+/// - `MismatchedPointer` if a `P#` pointer does not cover the type or the array of the register.
+/// - `PeripheralArea` if the address is in the peripheral I/O (`PIW256`).
+/// - `InvalidLength` if the capacity of a string exceeds the maximum of its type.
+/// - the struct type errors if the struct type of the register cannot be built.
+pub(crate) fn build_register(
+    f: RegistersFormat,
+    types: &HashMap<String, Vec<FieldFormat>>,
+) -> Result<Register, JsonReadError> {
+    let parsed = address::parse(&f.id).map_err(|err| JsonReadError::Address {
+        name: f.name.clone(),
+        err,
+    })?;
+    if parsed.area.is_peripheral() {
+        return Err(JsonReadError::PeripheralArea {
+            name: f.name,
+            id: f.id,
+        });
+    }
+    check_length(&f.name, f.type_, f.length)?;
+    let (addr, array) = match parsed.pointer {
+        Some(pointer) => {
+            // a pointer (`P#DB1.DBX0.0 INT 10`) covers `count` elements of the register type,
+            // several elements make an array
+            let count = f.array.map_or(1, |bounds| bounds.count());
+            let start_bit = match parsed.width {
+                AddressWidth::Bit(bit) => bit,
+                _ => 0,
+            };
+            if pointer.data_type != f.type_
+                || (f.array.is_some() && usize::from(pointer.count) != count)
+                || (f.type_ != DataType::BOOL && start_bit != 0)
+            {
+                return Err(JsonReadError::MismatchedPointer {
+                    name: f.name,
+                    id: f.id,
+                });
+            }
+            let addr = match f.type_ {
+                DataType::BOOL => RegAddress::from(&parsed),
+                _ => RegAddress::Byte(ByteAddress {
+                    area: parsed.area,
+                    db: parsed.db,
+                    byte: parsed.byte,
+                }),
+            };
+            let array = match f.array {
+                None if pointer.count > 1 => {
+                    Some(ArrayBounds::from((0, i32::from(pointer.count) - 1)))
+                }
+                array => array,
+            };
+            (addr, array)
+        }
+        None => {
+            // `BOOL` registers need a bit address, the other types a byte address
+            if (f.type_ == DataType::BOOL) != matches!(parsed.width, AddressWidth::Bit(_)) {
+                return Err(JsonReadError::MismatchedAddress {
+                    name: f.name,
+                    id: f.id,
+                });
+            }
+            (RegAddress::from(&parsed), f.array)
+        }
+    };
+    let mut reg = Register::new(f.name.clone(), f.type_, addr);
+    reg.length = f.length;
+    reg.array = array;
+    reg.scaling = f.scaling;
+    reg.unit = f.unit;
+    reg.description = f.description;
+    reg.access = f.access;
+    reg.min = f.min;
+    reg.max = f.max;
+    reg.tags = f.tags;
+    reg.byte_order = f.byte_order;
+    reg.fields = struct_fields(
+        &f.name,
+        f.type_,
+        f.struct_.as_deref(),
+        types,
+        &mut Vec::new(),
+    )?;
+    check_width(&reg, &parsed, &f.id)?;
+    Ok(reg)
+}
+
+/// Checks the capacity of a string register against the maximum of its type
+///
+/// A `STRING` stores its capacity on one byte, its header cannot describe more than 254
+/// characters.
+///
+/// Errors :
+/// - `InvalidLength` if the length exceeds `DataType::max_length`.
+fn check_length(name: &str, type_: DataType, length: Option<u16>) -> Result<(), JsonReadError> {
+    match (type_.max_length(), length) {
+        (Some(max), Some(length)) if length > max => Err(JsonReadError::InvalidLength {
+            name: name.to_string(),
+            length,
+            max,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the width of a byte address against the size of the elements of a register
+///
+/// A `W` or `D` address must hold exactly one element (`DBW` for an `INT`, `DBD` for a `REAL`),
+/// a `B` address marks the start of any element. Variable-size types (strings and structs)
+/// need a `B` address.
+///
+/// Errors :
+/// - `MismatchedWidth` if a `W` or `D` address does not hold the element.
+fn check_width(reg: &Register, parsed: &Address, id: &str) -> Result<(), JsonReadError> {
+    let variable_size = matches!(
+        reg.data_type,
+        DataType::STRING | DataType::WSTRING | DataType::STRUCT
+    );
+    match (parsed.width, parsed.pointer) {
+        (AddressWidth::Word | AddressWidth::DWord, None)
+            if variable_size || parsed.size() != Some(reg.element_size()) =>
+        {
+            Err(JsonReadError::MismatchedWidth {
+                name: reg.name.clone(),
+                id: id.to_string(),
+                width: parsed.size().unwrap_or_default(),
+                size: reg.element_size(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Expands an instance of a template into its registers
+///
+/// Errors :
+/// - the struct type errors if the template cannot be built.
+/// - `InstanceOutOfRange` if a register is past the last byte of the data block.
+pub(crate) fn build_instance(
+    instance: &InstanceFormat,
+    types: &HashMap<String, Vec<FieldFormat>>,
+) -> Result<Vec<Register>, JsonReadError> {
+    let mut regs = build_fields(&instance.template, types, &mut Vec::new())?;
+    for reg in &mut regs {
+        reg.name = format!("{}{SEPARATOR}{}", instance.prefix, reg.name);
+        let (db, byte) = match &mut reg.addr {
+            RegAddress::Byte(addr) => (&mut addr.db, &mut addr.byte),
+            RegAddress::Bit(addr) => (&mut addr.db, &mut addr.byte),
+        };
+        *db = instance.db;
+        *byte =
+            byte.checked_add(instance.offset)
+                .ok_or_else(|| JsonReadError::InstanceOutOfRange {
+                    prefix: instance.prefix.clone(),
+                })?;
+    }
+    Ok(regs)
+}
+
 /// get the s7 register from a file
 /// 
 /// # Parameters
 /// 
-/// - `input` (`File`) - the file who contains the register
+/// - `input` (`File`) - the file who contains the register, either a list of registers or a
+///   document with struct `types`, `registers`, `groups` and `instances` of templates
 /// 
 /// # Returns
 /// 
 /// - `Result<HashMap<String, Register>, JsonReadError>` - the list of register with the name in key and the register in value;
 /// if failed return a JsonReadError
+///
+/// The definitions are not checked beyond what is needed to build the registers (e.g. the
+/// registers may overlap), see `validation::validate` to check a file.
 /// 
 pub fn get_defs_from_json(input: File) -> Result<HashMap<String, Register>, JsonReadError> {
-    let raw: Vec<RegistersFormat> = serde_json::from_reader(input)?;
+    let raw: serde_json::Value = serde_json::from_reader(input)?;
+    build_defs(DefinitionsFormat::from_value(raw)?)
+}
+
+/// Builds the registers of a definition document, whatever the format it was read from
+///
+/// The groups are added to the `tags` of their registers, in the order of the group names.
+///
+/// Errors :
+/// - the errors of `build_register`.
+/// - `DuplicateRegister` if two registers (listed or generated by the instances of templates)
+///   have the same name, instead of keeping one of them.
+/// - `UnknownGroupMember` if a group lists a register that is not defined.
+pub(crate) fn build_defs(
+    defs: DefinitionsFormat,
+) -> Result<HashMap<String, Register>, JsonReadError> {
     let mut m = HashMap::<String, Register>::new();
-    for f in raw {
-        let addr: RegAddress = match f.type_ {
-            DataType::BOOL => {
-                let res: BitAddress = de_regex::from_str(f.id.as_str(), BIT_ADDRESS_REGEX)?;
-                res.into()
+    let mut insert = |reg: Register| match m.entry(reg.name.clone()) {
+        Entry::Occupied(_) => Err(JsonReadError::DuplicateRegister { name: reg.name }),
+        Entry::Vacant(entry) => {
+            entry.insert(reg);
+            Ok(())
+        }
+    };
+    for f in defs.registers {
+        insert(build_register(f, &defs.types)?)?;
+    }
+    for instance in &defs.instances {
+        for reg in build_instance(instance, &defs.types)? {
+            insert(reg)?;
+        }
+    }
+    let mut groups: Vec<_> = defs.groups.into_iter().collect();
+    groups.sort();
+    for (group, names) in groups {
+        for name in names {
+            let reg = m
+                .get_mut(&name)
+                .ok_or_else(|| JsonReadError::UnknownGroupMember {
+                    group: group.clone(),
+                    name,
+                })?;
+            if !reg.tags.contains(&group) {
+                reg.tags.push(group.clone());
             }
-            DataType::FLOAT | DataType::INT32 => {
-                let res: ByteAddress = de_regex::from_str(f.id.as_str(), BYTE_ADDRESS_REGEX)?;
-                res.into()
+        }
+    }
+    Ok(m)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Export formats of the Step 7 classic symbol tables
+pub enum SymbolTableFormat {
+    /// System Data Format, quoted comma separated values (`"Start","I       0.0","BOOL","Start button"`)
+    Sdf,
+    /// Fixed width records of 24, 12, 10 and 80 characters after the record length (`126,`)
+    Asc,
+    /// Assignment list, tab separated (`=`, address, data type, symbol, comment), the data type
+    /// is usually empty and then deduced from the width of the address
+    Seq,
+}
+
+impl SymbolTableFormat {
+    /// Format of a file from its extension (`sdf`, `asc`, `seq`, case insensitive).
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "sdf" => Some(SymbolTableFormat::Sdf),
+            "asc" => Some(SymbolTableFormat::Asc),
+            "seq" => Some(SymbolTableFormat::Seq),
+            _ => None,
+        }
+    }
+}
+
+custom_error! {pub SymbolTableError
+    Io{ err: std::io::Error } = "Read error : {err}",
+    Syntax{ line: usize } = "Invalid symbol entry at line {line}",
+    Address{ line: usize, name: String, err: AddressError } = "Invalid address of the symbol {name} at line {line} : {err}",
+    UnknownType{ line: usize, name: String, type_: String } = "Unknown data type {type_} of the symbol {name} at line {line}",
+    MismatchedAddress{ line: usize, name: String, id: String } = "The address {id} does not match the type of the symbol {name} at line {line}",
+    PeripheralArea{ line: usize, name: String, id: String } = "The address {id} of the symbol {name} at line {line} is in the peripheral I/O, which cannot be accessed",
+    DuplicateRegister{ line: usize, name: String } = "The symbol {name} at line {line} is already defined"
+}
+
+impl From<std::io::Error> for SymbolTableError {
+    fn from(value: std::io::Error) -> Self {
+        SymbolTableError::Io { err: value }
+    }
+}
+
+/// Reads a text file exported by the Siemens tools, Step 7 classic writes them in
+/// Windows-1252, they are decoded as such when they are not valid UTF-8.
+pub(crate) fn read_text(input: &mut File) -> std::io::Result<String> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8(bytes).unwrap_or_else(|err| {
+        let (text, _) = WINDOWS_1252.decode_without_bom_handling(err.as_bytes());
+        text.into_owned()
+    }))
+}
+
+/// Address mnemonics of the symbols that are not data (blocks, timers, counters), English and German
+const NON_DATA_MNEMONICS: [&str; 13] = [
+    "T", "C", "Z", "OB", "FB", "FC", "SFB", "SFC", "DB", "DI", "SDB", "UDT", "VAT",
+];
+
+/// Splits a line of quoted comma separated values (`"a","b"`), the quotes are optional and
+/// doubled quotes stand for a quote.
+fn split_sdf(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
             }
-            DataType::INT16 => {
-                let res: ByteAddress = de_regex::from_str(f.id.as_str(), WORD_ADDRESS_REGEX)?;
-                res.into()
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Extracts the symbol, address, data type and comment of a line, `None` for a malformed line.
+fn symbol_fields(line: &str, format: SymbolTableFormat) -> Option<[String; 4]> {
+    let fields: Vec<String> = match format {
+        SymbolTableFormat::Sdf => split_sdf(line),
+        SymbolTableFormat::Asc => {
+            let (_, record) = line.split_once(',')?;
+            let chars: Vec<char> = record.chars().collect();
+            [(0, 24), (24, 36), (36, 46), (46, 126)]
+                .iter()
+                .map(|&(start, end)| {
+                    chars[start.min(chars.len())..end.min(chars.len())]
+                        .iter()
+                        .collect()
+                })
+                .collect()
+        }
+        SymbolTableFormat::Seq => {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let field = |i: usize| fields.get(i).copied().unwrap_or_default().to_string();
+            vec![field(3), field(1), field(2), field(4)]
+        }
+    };
+    match &fields[..] {
+        [symbol, address, data_type, comment @ ..] if comment.len() <= 1 => Some([
+            symbol.trim().to_string(),
+            address.trim().to_string(),
+            data_type.trim().to_string(),
+            comment.first().map_or("", |c| c.trim()).to_string(),
+        ]),
+        _ => None,
+    }
+}
+
+/// Builds the register of a symbol, `None` for the symbols of blocks, timers and counters.
+///
+/// An empty data type is deduced from the width of the address (`BOOL`, `BYTE`, `WORD` or
+/// `DWORD`), the data type names are case insensitive (`Int`, `INT`).
+pub(crate) fn symbol_register(
+    line: usize,
+    name: String,
+    id: &str,
+    type_: &str,
+    comment: String,
+) -> Result<Option<Register>, SymbolTableError> {
+    // `MW  10` is written `MW10` by the address parser
+    let id: String = id.split_whitespace().collect();
+    let mnemonic: String = id
+        .trim_start_matches('%')
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    if NON_DATA_MNEMONICS
+        .iter()
+        .any(|m| m.eq_ignore_ascii_case(&mnemonic))
+    {
+        return Ok(None);
+    }
+    let parsed = address::parse(&id).map_err(|err| SymbolTableError::Address {
+        line,
+        name: name.clone(),
+        err,
+    })?;
+    if parsed.area.is_peripheral() {
+        return Err(SymbolTableError::PeripheralArea { line, name, id });
+    }
+    let data_type = match (type_.is_empty(), parsed.width) {
+        (true, AddressWidth::Bit(_)) => DataType::BOOL,
+        (true, AddressWidth::Byte) => DataType::BYTE,
+        (true, AddressWidth::Word) => DataType::WORD,
+        (true, AddressWidth::DWord) => DataType::DWORD,
+        (false, _) => DataType::from_name(&type_.to_ascii_uppercase()).ok_or_else(|| {
+            SymbolTableError::UnknownType {
+                line,
+                name: name.clone(),
+                type_: type_.to_string(),
             }
-        };
-        m.insert(
-            f.name.clone(),
-            Register {
-                name: f.name,
-                addr,
-                data_type: f.type_,
-            },
-        );
+        })?,
+    };
+    let size_matches = match parsed.size() {
+        None => data_type == DataType::BOOL,
+        Some(size) => data_type.size() == size,
+    };
+    if !size_matches {
+        return Err(SymbolTableError::MismatchedAddress { line, name, id });
+    }
+    let mut reg = Register::new(name, data_type, RegAddress::from(&parsed));
+    reg.description = Some(comment).filter(|comment| !comment.is_empty());
+    Ok(Some(reg))
+}
+
+/// get the s7 registers from a Step 7 classic symbol table
+///
+/// # Parameters
+///
+/// - `input` (`File`) - the exported symbol table, in UTF-8 or in the Windows code page of Step 7
+/// - `format` (`SymbolTableFormat`) - the export format of the table, see `SymbolTableFormat::from_extension`
+///
+/// # Returns
+///
+/// - `Result<HashMap<String, Register>, SymbolTableError>` - the registers named after the
+///   symbols, with the comments as description; if failed return a SymbolTableError
+///
+/// The symbols of blocks, timers and counters are skipped, as well as the empty lines. The
+/// symbols of the peripheral I/O (`PIW 256`) are rejected, they cannot be accessed, as well as
+/// the names defined twice.
+///
+pub fn get_defs_from_symbols(
+    mut input: File,
+    format: SymbolTableFormat,
+) -> Result<HashMap<String, Register>, SymbolTableError> {
+    let text = read_text(&mut input)?;
+    let mut m = HashMap::<String, Register>::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let [name, id, type_, comment] =
+            symbol_fields(line, format).ok_or(SymbolTableError::Syntax { line: line_number })?;
+        if let Some(reg) = symbol_register(line_number, name, &id, &type_, comment)? {
+            insert_symbol(&mut m, line_number, reg)?;
+        }
     }
     Ok(m)
 }
+
+/// Adds the register of a symbol to the registers of a table.
+///
+/// Errors :
+/// `DuplicateRegister` if the table already holds a symbol of the same name, instead of
+/// keeping one of them.
+pub(crate) fn insert_symbol(
+    m: &mut HashMap<String, Register>,
+    line: usize,
+    reg: Register,
+) -> Result<(), SymbolTableError> {
+    match m.entry(reg.name.clone()) {
+        Entry::Occupied(_) => Err(SymbolTableError::DuplicateRegister {
+            line,
+            name: reg.name,
+        }),
+        Entry::Vacant(entry) => {
+            entry.insert(reg);
+            Ok(())
+        }
+    }
+}
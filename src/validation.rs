@@ -0,0 +1,551 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{self, Read},
+};
+
+use serde::Serialize;
+
+use crate::address::{self, AddressWidth};
+use crate::types::{ByteOrder, DataType, MemoryArea, RegAddress, Register};
+use crate::utils::{build_instance, build_register, DefinitionsFormat, RegistersFormat};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// Severity of a validation issue.
+///
+/// Variants :
+/// * `Error` - the definition is wrong, the file is rejected or the register is unusable.
+/// * `Warning` - the definition is suspicious but usable (e.g. overlapping registers).
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// Position in a definition file, both starting at `1`.
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+/// Issue found in a definition file.
+///
+/// Properties:
+/// * `severity`: whether the issue is an error or a warning.
+/// * `register`: name of the register concerned, `None` for issues about the whole file.
+/// * `position`: position of the definition of the register in the file, when known.
+/// * `message`: description of the issue.
+pub struct Issue {
+    pub severity: Severity,
+    pub register: Option<String>,
+    pub position: Option<Position>,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "error")?,
+            Severity::Warning => write!(f, "warning")?,
+        }
+        if let Some(position) = &self.position {
+            write!(f, " at line {}, column {}", position.line, position.column)?;
+        }
+        if let Some(register) = &self.register {
+            write!(f, " in {register}")?;
+        }
+        write!(f, " : {}", self.message)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+/// Result of the validation of a definition file, the issues are sorted by position.
+pub struct ValidationReport {
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Issues preventing the use of the file.
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Suspicious definitions.
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    /// Whether the file has no error, warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        register: Option<&str>,
+        position: Option<Position>,
+        message: String,
+    ) {
+        self.issues.push(Issue {
+            severity,
+            register: register.map(str::to_string),
+            position,
+            message,
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    /// One issue per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validates a definition file, as read by `utils::get_defs_from_json`.
+///
+/// Errors :
+/// Returns an `io::Error` if the file cannot be read, the problems of the definitions
+/// themselves are reported in the `ValidationReport`.
+pub fn validate(mut input: File) -> Result<ValidationReport, io::Error> {
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+    Ok(validate_str(&text))
+}
+
+/// Validates the content of a definition file.
+///
+/// The following problems are reported as errors : invalid JSON or register definition,
+/// invalid address (e.g. a bit above `7`), address not matching the type (a `BOOL` on a byte
+/// address, a `DBW` holding a `DINT`), duplicated names, invalid array bounds, string
+/// capacity or scaling. Overlapping registers, multi-byte values at odd offsets in data
+/// blocks, multi-byte values on a `B` address (`DBB`, `MB`), which only marks their start,
+/// and ignored properties are reported as warnings. The registers expanded from the
+/// instances of templates are checked as the other registers, without position.
+pub fn validate_str(text: &str) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let raw: serde_json::Value = match serde_json::from_str(text) {
+        Ok(raw) => raw,
+        Err(err) => {
+            let position = Position {
+                line: err.line(),
+                column: err.column(),
+            };
+            report.push(Severity::Error, None, Some(position), err.to_string());
+            return report;
+        }
+    };
+    let defs: DefinitionsFormat<serde_json::Value> = match DefinitionsFormat::from_value(raw) {
+        Ok(defs) => defs,
+        Err(err) => {
+            report.push(Severity::Error, None, None, err.to_string());
+            return report;
+        }
+    };
+    let positions = register_positions(text);
+
+    let mut names: HashMap<String, Option<Position>> = HashMap::new();
+    let mut regs: Vec<(Register, Option<Position>)> = Vec::new();
+    for (i, entry) in defs.registers.into_iter().enumerate() {
+        let position = positions.get(i).copied();
+        let f: RegistersFormat = match serde_json::from_value(entry) {
+            Ok(f) => f,
+            Err(err) => {
+                report.push(Severity::Error, None, position, err.to_string());
+                continue;
+            }
+        };
+        let name = f.name.clone();
+        if let Some(first) = names.insert(name.clone(), position) {
+            let message = match first {
+                Some(first) => format!("duplicated name, first defined at line {}", first.line),
+                None => "duplicated name".to_string(),
+            };
+            report.push(Severity::Error, Some(&name), position, message);
+        }
+        check_definition(&f, position, &mut report);
+        match build_register(f, &defs.types) {
+            Ok(reg) => {
+                check_register(&reg, position, &mut report);
+                regs.push((reg, position));
+            }
+            Err(err) => report.push(Severity::Error, Some(&name), position, err.to_string()),
+        }
+    }
+    for instance in &defs.instances {
+        match build_instance(instance, &defs.types) {
+            Ok(instance_regs) => {
+                for reg in instance_regs {
+                    if names.insert(reg.name.clone(), None).is_some() {
+                        let message = format!(
+                            "duplicated name, also defined by the instance {}",
+                            instance.prefix
+                        );
+                        report.push(Severity::Error, Some(&reg.name), None, message);
+                    }
+                    check_register(&reg, None, &mut report);
+                    regs.push((reg, None));
+                }
+            }
+            Err(err) => report.push(
+                Severity::Error,
+                Some(&instance.prefix),
+                None,
+                err.to_string(),
+            ),
+        }
+    }
+    check_overlaps(&regs, &mut report);
+    let mut groups: Vec<_> = defs.groups.iter().collect();
+    groups.sort();
+    for (group, members) in groups {
+        for name in members.iter().filter(|name| !names.contains_key(*name)) {
+            let message = format!("the group {group} contains the undefined register {name}");
+            report.push(Severity::Error, None, None, message);
+        }
+    }
+
+    report
+        .issues
+        .sort_by_key(|issue| (issue.position.map(|p| (p.line, p.column)), issue.severity));
+    report
+}
+
+/// Validates registers already loaded, from any definition format or importer.
+///
+/// The checks of `validate_str` on the registers themselves (array bounds, string capacity,
+/// scaling, limits, alignment and overlaps) are run, the issues have no position.
+pub fn validate_defs(defs: &HashMap<String, Register>) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let mut regs: Vec<(Register, Option<Position>)> =
+        defs.values().map(|reg| (reg.clone(), None)).collect();
+    regs.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+    for (reg, _) in &regs {
+        check_register(reg, None, &mut report);
+    }
+    check_overlaps(&regs, &mut report);
+    report
+}
+
+/// Checks the raw definition of a register against its declared type.
+fn check_definition(
+    f: &RegistersFormat,
+    position: Option<Position>,
+    report: &mut ValidationReport,
+) {
+    let name = Some(f.name.as_str());
+    if f.struct_.is_some() && f.type_ != DataType::STRUCT {
+        let message = format!(
+            "the struct type is ignored by a {} register",
+            f.type_.name()
+        );
+        report.push(Severity::Warning, name, position, message);
+    }
+    if f.length.is_some() && !matches!(f.type_, DataType::STRING | DataType::WSTRING) {
+        let message = format!("the length is ignored by a {} register", f.type_.name());
+        report.push(Severity::Warning, name, position, message);
+    }
+    // the `W` and `D` widths are checked when building the register, a `B` address only marks
+    // the start of a longer value
+    let Ok(parsed) = address::parse(&f.id) else {
+        return;
+    };
+    let variable_size = matches!(
+        f.type_,
+        DataType::STRING | DataType::WSTRING | DataType::STRUCT
+    );
+    if parsed.width == AddressWidth::Byte
+        && parsed.pointer.is_none()
+        && !variable_size
+        && f.type_.size() > 1
+    {
+        let message = format!(
+            "the address {} only marks the start of the {} bytes of a {}",
+            f.id,
+            f.type_.size(),
+            f.type_.name()
+        );
+        report.push(Severity::Warning, name, position, message);
+    }
+}
+
+/// Checks a register built from its definition.
+fn check_register(reg: &Register, position: Option<Position>, report: &mut ValidationReport) {
+    let name = Some(reg.name.as_str());
+    if reg.area().is_peripheral() {
+        let message = format!(
+            "the address {} is in the peripheral I/O, which cannot be accessed",
+            reg.addr
+        );
+        report.push(Severity::Error, name, position, message);
+    }
+    if let Some(bounds) = reg.array {
+        if bounds.upper < bounds.lower {
+            let message = format!(
+                "the array upper bound {} is below the lower bound {}",
+                bounds.upper, bounds.lower
+            );
+            report.push(Severity::Error, name, position, message);
+        }
+    }
+    if let Some(max_length) = reg.data_type.max_length() {
+        if reg.string_length() > max_length {
+            let message = format!(
+                "the length {} exceeds the maximum length {max_length} of a {}",
+                reg.string_length(),
+                reg.data_type.name()
+            );
+            report.push(Severity::Error, name, position, message);
+        }
+    }
+    let end = reg
+        .checked_size()
+        .and_then(|size| reg.byte_offset().checked_add(size));
+    if end.is_none() {
+        let message = "the register exceeds the 65535 bytes of a memory area".to_string();
+        report.push(Severity::Error, name, position, message);
+    }
+    if let Some(scaling) = &reg.scaling {
+        let (factor, offset) = scaling.factor_offset();
+        if !reg.data_type.is_numeric() {
+            let message = format!("a {} register cannot be scaled", reg.data_type.name());
+            report.push(Severity::Error, name, position, message);
+        } else if !factor.is_finite() || !offset.is_finite() || factor == 0.0 {
+            let message = "the scaling cannot be inverted (empty range or null factor)".to_string();
+            report.push(Severity::Error, name, position, message);
+        }
+    }
+    if (reg.min.is_some() || reg.max.is_some()) && !reg.data_type.is_numeric() {
+        let message = format!(
+            "the limits are ignored by a {} register",
+            reg.data_type.name()
+        );
+        report.push(Severity::Warning, name, position, message);
+    }
+    if reg.byte_order != ByteOrder::ABCD && (!reg.data_type.is_numeric() || reg.element_size() < 2)
+    {
+        let message = format!(
+            "the byte order is ignored by a {} register",
+            reg.data_type.name()
+        );
+        report.push(Severity::Warning, name, position, message);
+    }
+    if let (Some(min), Some(max)) = (reg.min, reg.max) {
+        if min > max {
+            let message = format!("the minimum {min} is above the maximum {max}");
+            report.push(Severity::Error, name, position, message);
+        }
+    }
+    if let RegAddress::Byte(addr) = &reg.addr {
+        if addr.area == MemoryArea::DataBlock && reg.element_size() > 1 && addr.byte % 2 == 1 {
+            let message = format!(
+                "a {} at the odd offset {} is not aligned as in non-optimized blocks",
+                reg.data_type.name(),
+                addr.byte
+            );
+            report.push(Severity::Warning, name, position, message);
+        }
+    }
+}
+
+/// Memory area, data block and range of bits covered by a register.
+type Extent = (MemoryArea, u16, u64, u64);
+
+/// Computes the extent of a register.
+fn extent(reg: &Register) -> Extent {
+    match &reg.addr {
+        RegAddress::Bit(addr) => {
+            let start = u64::from(addr.byte) * 8 + u64::from(addr.bit);
+            let count = reg.array.map_or(1, |bounds| bounds.count() as u64);
+            (addr.area, addr.db, start, start + count)
+        }
+        RegAddress::Byte(addr) => {
+            let start = u64::from(addr.byte) * 8;
+            (addr.area, addr.db, start, start + u64::from(reg.size()) * 8)
+        }
+    }
+}
+
+/// Reports the registers sharing some memory with a register defined before them.
+fn check_overlaps(regs: &[(Register, Option<Position>)], report: &mut ValidationReport) {
+    let mut extents: Vec<_> = regs
+        .iter()
+        .map(|(reg, position)| (extent(reg), reg, position))
+        .collect();
+    extents.sort_by_key(|((area, db, start, end), ..)| (*area as u8, *db, *start, *end));
+    let mut widest: Option<(Extent, &Register)> = None;
+    for (extent, reg, position) in extents {
+        let (area, db, start, end) = extent;
+        match widest {
+            Some(((w_area, w_db, _, w_end), other))
+                if w_area == area && w_db == db && start < w_end =>
+            {
+                let message = format!("overlaps the register {}", other.name);
+                report.push(Severity::Warning, Some(&reg.name), *position, message);
+                if end > w_end {
+                    widest = Some((extent, reg));
+                }
+            }
+            _ => widest = Some((extent, reg)),
+        }
+    }
+}
+
+/// Finds the position of each register definition in the text of a definition file.
+///
+/// Returns an empty list if the text is not valid JSON, the positions are only used to locate
+/// the issues.
+fn register_positions(text: &str) -> Vec<Position> {
+    let mut scanner = Scanner {
+        chars: text.chars().collect(),
+        pos: 0,
+        line: 1,
+        column: 1,
+    };
+    scanner.registers().unwrap_or_default()
+}
+
+/// Minimal JSON scanner tracking lines and columns.
+struct Scanner {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Scanner {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        self.skip_whitespaces();
+        (self.bump()? == c).then_some(())
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect('"')?;
+        let mut res = String::new();
+        loop {
+            match self.bump()? {
+                '"' => return Some(res),
+                '\\' => res.push(self.bump()?),
+                c => res.push(c),
+            }
+        }
+    }
+
+    /// Calls `element` on each element of an array, positioned on the element.
+    fn array(&mut self, mut element: impl FnMut(&mut Self) -> Option<()>) -> Option<()> {
+        self.expect('[')?;
+        self.skip_whitespaces();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Some(());
+        }
+        loop {
+            self.skip_whitespaces();
+            element(self)?;
+            self.skip_whitespaces();
+            match self.bump()? {
+                ',' => continue,
+                ']' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Calls `member` with the key of each member of an object, positioned on the value.
+    fn object(&mut self, mut member: impl FnMut(&mut Self, &str) -> Option<()>) -> Option<()> {
+        self.expect('{')?;
+        self.skip_whitespaces();
+        if self.peek() == Some('}') {
+            self.bump();
+            return Some(());
+        }
+        loop {
+            self.skip_whitespaces();
+            let key = self.string()?;
+            self.expect(':')?;
+            self.skip_whitespaces();
+            member(self, &key)?;
+            self.skip_whitespaces();
+            match self.bump()? {
+                ',' => continue,
+                '}' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        self.skip_whitespaces();
+        match self.peek()? {
+            '{' => self.object(|scanner, _| scanner.skip_value()),
+            '[' => self.array(Self::skip_value),
+            '"' => self.string().map(|_| ()),
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|c| !matches!(c, ',' | ']' | '}') && !c.is_whitespace())
+                {
+                    self.bump();
+                }
+                Some(())
+            }
+        }
+    }
+
+    /// Positions of the elements of the register list, at the top level or in `registers`.
+    fn registers(&mut self) -> Option<Vec<Position>> {
+        let mut res = Vec::new();
+        let mut element = |scanner: &mut Self| {
+            res.push(scanner.position());
+            scanner.skip_value()
+        };
+        self.skip_whitespaces();
+        match self.peek()? {
+            '[' => self.array(&mut element)?,
+            _ => self.object(|scanner, key| match key {
+                "registers" => scanner.array(&mut element),
+                _ => scanner.skip_value(),
+            })?,
+        }
+        Some(res)
+    }
+}
@@ -1,7 +1,23 @@
+use s7_device::address::{self, AddressError, AddressWidth};
+use s7_device::datetime::{NaiveDate, TimeDelta};
+use s7_device::errors::S7Error;
+use s7_device::formats::{self, DefinitionFormat, FormatError};
+use s7_device::import::{awl, scl, source::SourceError, xlsx};
+use s7_device::layout::{self, array_member, member, struct_member};
+use s7_device::manifest::{self, ManifestError};
+use s7_device::reload::{DefinitionsPoller, ReloadError};
 use s7_device::s7_connexion::S7Connexion;
+use s7_device::schema;
+use s7_device::types::{
+    Access, BitAddress, ByteAddress, ByteOrder, DataType, LimitPolicy, MemoryArea, RegAddress,
+    Register, Scaling,
+};
+use s7_device::validation::{self, Severity};
 use s7_device::{types::RegisterValue, utils, S7Device};
+use std::collections::HashMap;
 use std::fs::File;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
 use testcontainers::core::WaitFor;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::{ContainerAsync, GenericImage};
@@ -14,6 +30,1448 @@ fn test_defs_read() {
     assert!(defs.len() == 6, "{0}", defs.len());
 }
 
+#[test]
+fn test_defs_read_areas() {
+    let file = File::open("tests/test_registers_areas.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+    assert!(defs.len() == 6, "{0}", defs.len());
+
+    match &defs["Lamp"].addr {
+        RegAddress::Bit(addr) => {
+            assert_eq!(addr.area, MemoryArea::Output);
+            assert_eq!((addr.byte, addr.bit), (4, 1));
+        }
+        addr => panic!("unexpected address {addr:?}"),
+    }
+    match &defs["Setpoint"].addr {
+        RegAddress::Byte(addr) => {
+            assert_eq!(addr.area, MemoryArea::Flag);
+            assert_eq!(addr.byte, 100);
+        }
+        addr => panic!("unexpected address {addr:?}"),
+    }
+    match &defs["AnalogIn"].addr {
+        RegAddress::Byte(addr) => assert_eq!(addr.area, MemoryArea::Input),
+        addr => panic!("unexpected address {addr:?}"),
+    }
+
+    // `s7_client` cannot access the peripheral I/O, the registers are rejected when loading
+    let text = std::fs::read_to_string("tests/test_registers_areas.json").unwrap();
+    let peripheral = text.replace(r#""IW256""#, r#""PIW256""#);
+    assert!(matches!(
+        formats::read_defs(peripheral.as_bytes(), DefinitionFormat::Json),
+        Err(FormatError::Definitions {
+            err: utils::JsonReadError::PeripheralArea { name, .. }
+        }) if name == "AnalogIn"
+    ));
+    let report = validation::validate_str(&peripheral);
+    assert!(report
+        .errors()
+        .any(|issue| issue.register.as_deref() == Some("AnalogIn")));
+    let mut defs = defs;
+    defs.get_mut("AnalogIn").unwrap().addr = "PIW256".parse().unwrap();
+    assert!(!validation::validate_defs(&defs).is_valid());
+    let path = std::env::temp_dir().join("s7_device_symbols_peripheral.seq");
+    std::fs::write(&path, "=\tPIW 256\tWORD\tRaw\t\r\n").unwrap();
+    assert!(matches!(
+        utils::get_defs_from_symbols(File::open(&path).unwrap(), utils::SymbolTableFormat::Seq),
+        Err(utils::SymbolTableError::PeripheralArea { line: 1, .. })
+    ));
+}
+
+#[test]
+fn test_address_parser() {
+    let cases = [
+        (
+            "DB1.DBX2.3",
+            MemoryArea::DataBlock,
+            1,
+            2,
+            AddressWidth::Bit(3),
+        ),
+        (
+            "%DB10.DBD8",
+            MemoryArea::DataBlock,
+            10,
+            8,
+            AddressWidth::DWord,
+        ),
+        ("db2.dbb4", MemoryArea::DataBlock, 2, 4, AddressWidth::Byte),
+        ("%I0.7", MemoryArea::Input, 0, 0, AddressWidth::Bit(7)),
+        ("E1.0", MemoryArea::Input, 0, 1, AddressWidth::Bit(0)),
+        ("%QX4.1", MemoryArea::Output, 0, 4, AddressWidth::Bit(1)),
+        ("AW6", MemoryArea::Output, 0, 6, AddressWidth::Word),
+        ("%MW20", MemoryArea::Flag, 0, 20, AddressWidth::Word),
+        ("MD100", MemoryArea::Flag, 0, 100, AddressWidth::DWord),
+        (
+            "PEW256",
+            MemoryArea::PeripheralInput,
+            0,
+            256,
+            AddressWidth::Word,
+        ),
+        (
+            "%PQB8",
+            MemoryArea::PeripheralOutput,
+            0,
+            8,
+            AddressWidth::Byte,
+        ),
+    ];
+    for (id, area, db, byte, width) in cases {
+        let addr = address::parse(id).unwrap();
+        assert_eq!(
+            (addr.area, addr.db, addr.byte, addr.width),
+            (area, db, byte, width),
+            "{id}"
+        );
+        assert!(addr.pointer.is_none());
+    }
+
+    let pointer = address::parse("P#DB1.DBX4.0 BYTE 10").unwrap();
+    assert_eq!((pointer.db, pointer.byte), (1, 4));
+    let range = pointer.pointer.unwrap();
+    assert_eq!((range.data_type, range.count), (DataType::BYTE, 10));
+
+    assert!(matches!(
+        address::parse("DB1.DBZ0"),
+        Err(AddressError::UnexpectedChar {
+            column: 7,
+            found: 'Z',
+            ..
+        })
+    ));
+    assert!(matches!(
+        address::parse("MW"),
+        Err(AddressError::UnexpectedEnd { column: 3, .. })
+    ));
+    assert!(matches!(
+        address::parse("I0.8"),
+        Err(AddressError::InvalidBit { column: 4, .. })
+    ));
+    assert!(matches!(
+        address::parse("PI0.0"),
+        Err(AddressError::UnexpectedChar { column: 3, .. })
+    ));
+    assert!(matches!(
+        address::parse("MW70000"),
+        Err(AddressError::NumberOutOfRange { column: 3, .. })
+    ));
+    assert!(matches!(
+        address::parse("P#M0.0 FOO 2"),
+        Err(AddressError::UnknownPointerType { column: 8, .. })
+    ));
+}
+
+#[test]
+fn test_defs_address_width() {
+    let load = |json: &str| {
+        let path = std::env::temp_dir().join(format!("s7_device_width_{}.json", json.len()));
+        std::fs::write(&path, json).unwrap();
+        utils::get_defs_from_json(File::open(&path).unwrap())
+    };
+    let defs = load(r#"[{ "name": "A", "id": "P#DB1.DBX4.0 INT 10", "type": "INT" }]"#).unwrap();
+    assert_eq!(defs["A"].addr.to_string(), "DB1.DBB4");
+    assert_eq!(defs["A"].array, Some((0, 9).into()));
+    assert_eq!(defs["A"].size(), 20);
+    let defs =
+        load(r#"[{ "name": "B", "id": "P#DB1.DBX2.3 BOOL 4", "type": "BOOL", "array": [1, 4] }]"#)
+            .unwrap();
+    assert_eq!(defs["B"].addr.to_string(), "DB1.DBX2.3");
+    assert_eq!(defs["B"].array, Some((1, 4).into()));
+    let defs = load(r#"[{ "name": "C", "id": "P#M10.0 REAL 1", "type": "REAL" }]"#).unwrap();
+    assert_eq!(
+        (defs["C"].addr.to_string().as_str(), defs["C"].array),
+        ("MB10", None)
+    );
+    let defs = load(r#"[{ "name": "D", "id": "DB1.DBB0", "type": "REAL" }]"#).unwrap();
+    assert_eq!(defs["D"].size(), 4);
+
+    for json in [
+        r#"[{ "name": "E", "id": "DB1.DBW0", "type": "REAL" }]"#,
+        r#"[{ "name": "E", "id": "DB1.DBD0", "type": "INT" }]"#,
+        r#"[{ "name": "E", "id": "MD0", "type": "LREAL" }]"#,
+        r#"[{ "name": "E", "id": "DB1.DBW0", "type": "STRING", "length": 0 }]"#,
+    ] {
+        assert!(
+            matches!(
+                load(json),
+                Err(utils::JsonReadError::MismatchedWidth { .. })
+            ),
+            "{json}"
+        );
+    }
+    for json in [
+        r#"[{ "name": "F", "id": "P#DB1.DBX0.0 BYTE 4", "type": "INT" }]"#,
+        r#"[{ "name": "F", "id": "P#DB1.DBX0.0 INT 4", "type": "INT", "array": [0, 9] }]"#,
+        r#"[{ "name": "F", "id": "P#DB1.DBX0.1 INT 4", "type": "INT" }]"#,
+    ] {
+        assert!(
+            matches!(
+                load(json),
+                Err(utils::JsonReadError::MismatchedPointer { .. })
+            ),
+            "{json}"
+        );
+    }
+}
+
+#[test]
+fn test_address_round_trip() {
+    for id in [
+        "DB1.DBX2.3",
+        "DB10.DBD8",
+        "I0.7",
+        "Q4.1",
+        "MW20",
+        "PIW256",
+        "PIX0.1",
+        "P#DB1.DBX4.0 BYTE 10",
+        "P#MX10.0 INT16 4",
+    ] {
+        let addr = address::parse(id).unwrap();
+        assert_eq!(addr.to_string(), id);
+        assert_eq!(address::parse(&addr.to_string()).unwrap(), addr);
+    }
+
+    let addr: RegAddress = "%E1.2".parse().unwrap();
+    assert_eq!(addr.to_string(), "I1.2");
+    let addr: ByteAddress = "MD4".parse().unwrap();
+    assert_eq!(addr.to_string(), "MB4");
+    assert!("DB1.DBX0.0".parse::<ByteAddress>().is_err());
+    assert!("DB1.DBW0".parse::<BitAddress>().is_err());
+}
+
+#[test]
+fn test_register_serde() {
+    let file = File::open("tests/test_registers_structs.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+    let json = serde_json::to_string(&defs).unwrap();
+    let back: HashMap<String, Register> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, defs);
+
+    let reg: Register = serde_json::from_str(
+        r#"{"data_type": "INT", "name": "Level", "addr": "%DB4.DBW0", "unit": "%"}"#,
+    )
+    .unwrap();
+    assert_eq!(reg.data_type, DataType::INT16);
+    assert_eq!(reg.addr.to_string(), "DB4.DBB0");
+
+    let vals = [
+        RegisterValue::S16(-3),
+        RegisterValue::Char('A'),
+        RegisterValue::Date(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+        RegisterValue::Duration(TimeDelta::milliseconds(1500)),
+        RegisterValue::Array(vec![
+            RegisterValue::Boolean(true),
+            RegisterValue::Boolean(false),
+        ]),
+        RegisterValue::Struct(HashMap::from([(
+            "Speed".to_string(),
+            RegisterValue::Float32(1.5),
+        )])),
+    ];
+    for val in vals {
+        let json = serde_json::to_string(&val).unwrap();
+        assert_eq!(serde_json::from_str::<RegisterValue>(&json).unwrap(), val);
+    }
+    assert_eq!(
+        serde_json::to_string(&RegisterValue::S16(-3)).unwrap(),
+        r#"{"S16":-3}"#
+    );
+}
+
+#[test]
+fn test_elementary_types_conversion() {
+    let addr = ByteAddress {
+        area: MemoryArea::DataBlock,
+        db: 1,
+        byte: 0,
+    };
+    let reg = |data_type| Register::new("Test", data_type, addr.clone().into());
+    let cases = [
+        (DataType::BYTE, vec![0xAB], RegisterValue::U8(0xAB)),
+        (DataType::SINT, vec![0xFE], RegisterValue::S8(-2)),
+        (DataType::WORD, vec![0x12, 0x34], RegisterValue::U16(0x1234)),
+        (DataType::UDINT, vec![0, 1, 0, 0], RegisterValue::U32(65536)),
+        (
+            DataType::LINT,
+            (-5_i64).to_be_bytes().to_vec(),
+            RegisterValue::S64(-5),
+        ),
+        (
+            DataType::LREAL,
+            1.5_f64.to_be_bytes().to_vec(),
+            RegisterValue::Float64(1.5),
+        ),
+        (DataType::CHAR, vec![b'A'], RegisterValue::Char('A')),
+    ];
+    for (data_type, raw, expected) in cases {
+        let val: RegisterValue = (raw.clone(), reg(data_type)).try_into().unwrap();
+        assert_eq!(val, expected);
+        let encoded: Vec<u8> = val.try_into().unwrap();
+        assert_eq!(encoded, raw);
+    }
+
+    let res: Result<RegisterValue, _> = (vec![0, 1], reg(DataType::INT32)).try_into();
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_generic_value_conversion() {
+    use industrial_device::types::Value;
+
+    let value = |val: RegisterValue| Value::try_from(val);
+    assert!(matches!(value(RegisterValue::S64(-5)), Ok(Value::S32(-5))));
+    assert!(value(RegisterValue::S64(i64::from(i32::MAX) + 1)).is_err());
+    assert!(matches!(
+        value(RegisterValue::Duration(TimeDelta::milliseconds(1500))),
+        Ok(Value::S32(1500))
+    ));
+    assert!(value(RegisterValue::Duration(TimeDelta::days(30))).is_err());
+    assert!(matches!(value(RegisterValue::Float64(1.5)), Ok(Value::Float32(val)) if val == 1.5));
+    assert!(value(RegisterValue::Float64(1e300)).is_err());
+    assert!(matches!(value(RegisterValue::U8(7)), Ok(Value::U16(7))));
+    assert!(value(RegisterValue::Array(vec![RegisterValue::Boolean(true)])).is_err());
+    assert!(value(RegisterValue::Struct(HashMap::new())).is_err());
+}
+
+#[test]
+fn test_string_conversion() {
+    let addr = ByteAddress {
+        area: MemoryArea::DataBlock,
+        db: 1,
+        byte: 0,
+    };
+    let mut reg = Register::new("Recipe", DataType::STRING, addr.clone().into());
+    reg.length = Some(8);
+    assert_eq!(reg.size(), 10);
+
+    let raw = vec![8, 3, b'a', b'b', b'c', 0, 0, 0, 0, 0];
+    let val: RegisterValue = (raw, reg.clone()).try_into().unwrap();
+    assert_eq!(val, RegisterValue::String("abc".to_string()));
+    let encoded = reg.encode(val).unwrap();
+    assert_eq!(encoded, vec![8, 3, b'a', b'b', b'c']);
+
+    let too_long = RegisterValue::String("too long string".to_string());
+    assert!(reg.encode(too_long).is_err());
+
+    let mut reg = Register::new("Message", DataType::WSTRING, addr.clone().into());
+    reg.length = Some(4);
+    let encoded = reg.encode(RegisterValue::String("hé".to_string())).unwrap();
+    assert_eq!(encoded, vec![0, 4, 0, 2, 0, b'h', 0, 0xE9]);
+    let val: RegisterValue = (encoded, reg).try_into().unwrap();
+    assert_eq!(val, RegisterValue::String("hé".to_string()));
+
+    // the header of a `STRING` holds its capacity on one byte
+    let mut reg = Register::new("Label", DataType::STRING, addr.into());
+    reg.length = Some(300);
+    assert!(matches!(
+        reg.encode(RegisterValue::String("abc".to_string())),
+        Err(S7Error::InvalidStringLength {
+            capacity: 300,
+            max: 254
+        })
+    ));
+    let json = r#"[{ "name": "Label", "id": "DB1.DBB0", "type": "STRING", "length": 300 }]"#;
+    assert!(matches!(
+        formats::read_defs(json.as_bytes(), DefinitionFormat::Json),
+        Err(FormatError::Definitions {
+            err: utils::JsonReadError::InvalidLength { max: 254, .. }
+        })
+    ));
+    let json = r#"[{ "name": "Label", "id": "DB1.DBB0", "type": "WSTRING", "length": 16382 }]"#;
+    assert!(formats::read_defs(json.as_bytes(), DefinitionFormat::Json).is_ok());
+}
+
+#[test]
+fn test_datetime_conversion() {
+    let addr = ByteAddress {
+        area: MemoryArea::DataBlock,
+        db: 1,
+        byte: 0,
+    };
+    let reg = |data_type| Register::new("Test", data_type, addr.clone().into());
+    let stamp = NaiveDate::from_ymd_opt(2024, 3, 15)
+        .unwrap()
+        .and_hms_milli_opt(13, 45, 30, 123)
+        .unwrap();
+    let cases = [
+        (
+            DataType::DATE,
+            vec![0x30, 0xCC],
+            RegisterValue::Date(stamp.date()),
+        ),
+        (
+            DataType::DT,
+            vec![0x24, 0x03, 0x15, 0x13, 0x45, 0x30, 0x12, 0x36],
+            RegisterValue::DateTime(stamp),
+        ),
+        (
+            DataType::DTL,
+            vec![0x07, 0xE8, 3, 15, 6, 13, 45, 30, 0x07, 0x54, 0xD4, 0xC0],
+            RegisterValue::DateTime(stamp),
+        ),
+        (
+            DataType::S5TIME,
+            vec![0x21, 0x27],
+            RegisterValue::Duration(TimeDelta::seconds(127)),
+        ),
+        (
+            DataType::TIME,
+            (-1500_i32).to_be_bytes().to_vec(),
+            RegisterValue::Duration(TimeDelta::milliseconds(-1500)),
+        ),
+    ];
+    for (data_type, raw, expected) in cases {
+        let val: RegisterValue = (raw.clone(), reg(data_type)).try_into().unwrap();
+        assert_eq!(val, expected, "{data_type:?}");
+        assert_eq!(reg(data_type).encode(val).unwrap(), raw, "{data_type:?}");
+    }
+
+    let val: RegisterValue = (vec![0x21, 0x27], reg(DataType::S5TIME))
+        .try_into()
+        .unwrap();
+    assert_eq!(Duration::try_from(val).unwrap(), Duration::from_secs(127));
+    let negative = RegisterValue::Duration(TimeDelta::milliseconds(-1500));
+    assert!(Duration::try_from(negative).is_err());
+}
+
+#[test]
+fn test_array_registers() {
+    let file = File::open("tests/test_registers_arrays.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+
+    let flags = &defs["Flags"];
+    assert_eq!(flags.size(), 2);
+    let raw = vec![0b1001_0000, 0b0001_1110];
+    let val: RegisterValue = (raw.clone(), flags.clone()).try_into().unwrap();
+    let expected: Vec<RegisterValue> = [true, false, false, true, false, true, true, true]
+        .into_iter()
+        .map(RegisterValue::Boolean)
+        .collect();
+    assert_eq!(val, RegisterValue::Array(expected));
+    let cleared = RegisterValue::Array(vec![RegisterValue::Boolean(false); 8]);
+    assert_eq!(
+        flags.merge_bits(cleared, raw).unwrap(),
+        vec![0, 0b0001_0000]
+    );
+    match flags.element(5).unwrap().addr {
+        RegAddress::Bit(addr) => assert_eq!((addr.byte, addr.bit), (1, 1)),
+        addr => panic!("unexpected address {addr:?}"),
+    }
+
+    let speeds = &defs["Speeds"];
+    assert_eq!(speeds.size(), 8);
+    assert!(matches!(
+        speeds.element(0),
+        Err(S7Error::RegisterDoesNotExistsError)
+    ));
+    let element = speeds.element(3).unwrap();
+    assert_eq!(element.name, "Speeds[3]");
+    match element.addr {
+        RegAddress::Byte(addr) => assert_eq!(addr.byte, 6),
+        addr => panic!("unexpected address {addr:?}"),
+    }
+
+    // the elements past the end of the memory area have no address
+    let addr = ByteAddress {
+        area: MemoryArea::DataBlock,
+        db: 1,
+        byte: 65000,
+    };
+    let mut counters = Register::new("Counters", DataType::INT32, addr.into());
+    counters.array = Some((0, 19999).into());
+    assert!(counters.element(133).is_ok());
+    assert!(matches!(
+        counters.element(134),
+        Err(S7Error::AddressOverflowError { name }) if name == "Counters[134]"
+    ));
+    assert!(matches!(
+        counters.element(19999),
+        Err(S7Error::AddressOverflowError { .. })
+    ));
+    let val = RegisterValue::Array((1..=4).map(RegisterValue::S16).collect());
+    assert_eq!(speeds.encode(val).unwrap(), vec![0, 1, 0, 2, 0, 3, 0, 4]);
+
+    let names = &defs["Names"];
+    let val = RegisterValue::Array(vec![
+        RegisterValue::String("ab".to_string()),
+        RegisterValue::String("wxyz".to_string()),
+    ]);
+    let raw = names.encode(val.clone()).unwrap();
+    assert_eq!(
+        raw,
+        vec![4, 2, b'a', b'b', 0, 0, 4, 4, b'w', b'x', b'y', b'z']
+    );
+    let decoded: RegisterValue = (raw, names.clone()).try_into().unwrap();
+    assert_eq!(decoded, val);
+}
+
+#[test]
+fn test_struct_registers() {
+    let file = File::open("tests/test_registers_structs.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+
+    let motor = &defs["Motor1"];
+    assert_eq!(motor.size(), 8);
+    assert_eq!(defs["Motors"].size(), 24);
+    assert_eq!(defs["Conveyor1"].size(), 10);
+
+    let raw = vec![0x3F, 0xC0, 0, 0, 0b01, 0, 0x12, 0x34];
+    let val: RegisterValue = (raw.clone(), motor.clone()).try_into().unwrap();
+    let expected = HashMap::from([
+        ("Speed".to_string(), RegisterValue::Float32(1.5)),
+        ("Running".to_string(), RegisterValue::Boolean(true)),
+        ("Overload".to_string(), RegisterValue::Boolean(false)),
+        ("Fault".to_string(), RegisterValue::U16(0x1234)),
+    ]);
+    assert_eq!(val, RegisterValue::Struct(expected));
+
+    let update = RegisterValue::Struct(HashMap::from([(
+        "Overload".to_string(),
+        RegisterValue::Boolean(true),
+    )]));
+    let merged = motor.merge(update, raw).unwrap();
+    assert_eq!(merged, vec![0x3F, 0xC0, 0, 0, 0b11, 0, 0x12, 0x34]);
+
+    let speed = defs["Motors"].element(2).unwrap().field("Speed").unwrap();
+    assert_eq!(speed.name, "Motors[2].Speed");
+    match speed.addr {
+        RegAddress::Byte(addr) => assert_eq!((addr.db, addr.byte), (5, 16)),
+        addr => panic!("unexpected address {addr:?}"),
+    }
+    let length = defs["Conveyor1"].field("Length").unwrap();
+    assert_eq!(length.byte_offset(), 8);
+    assert!(matches!(
+        defs["Conveyor1"].field("Missing"),
+        Err(S7Error::RegisterDoesNotExistsError)
+    ));
+
+    // a field past the end of the memory area has no address
+    let mut conveyor = defs["Conveyor1"].clone();
+    conveyor.addr = "DB1.DBB65530".parse().unwrap();
+    assert!(matches!(
+        conveyor.field("Length"),
+        Err(S7Error::AddressOverflowError { name }) if name == "Conveyor1.Length"
+    ));
+}
+
+#[test]
+fn test_scaling() {
+    let file = File::open("tests/test_registers_scaling.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+
+    let level = &defs["Level"];
+    assert_eq!(level.unit.as_deref(), Some("%"));
+    let raw: RegisterValue = (vec![0x36, 0x00], level.clone()).try_into().unwrap();
+    assert_eq!(raw, RegisterValue::S16(13824));
+    assert_eq!(level.scale(raw).unwrap(), RegisterValue::Float64(50.0));
+    assert_eq!(
+        level.unscale(RegisterValue::Float64(100.0)).unwrap(),
+        RegisterValue::S16(27648)
+    );
+    assert!(level.unscale(RegisterValue::Float64(200.0)).is_err());
+
+    let temperature = &defs["Temperature"];
+    assert_eq!(
+        temperature.scale(RegisterValue::S16(725)).unwrap(),
+        RegisterValue::Float64(22.5)
+    );
+    assert_eq!(
+        temperature.unscale(RegisterValue::Float32(-10.0)).unwrap(),
+        RegisterValue::S16(400)
+    );
+
+    let counter = &defs["Counter"];
+    assert_eq!(
+        counter.scale(RegisterValue::S16(3)).unwrap(),
+        RegisterValue::S16(3)
+    );
+}
+
+#[test]
+fn test_validation() {
+    let file = File::open("tests/test_registers.json").unwrap();
+    let report = validation::validate(file).unwrap();
+    assert!(report.is_valid(), "{report}");
+
+    let file = File::open("tests/test_registers_invalid.json").unwrap();
+    let report = validation::validate(file).unwrap();
+    assert!(!report.is_valid());
+    let issues: Vec<(Severity, Option<&str>, usize)> = report
+        .issues
+        .iter()
+        .map(|issue| {
+            let line = issue.position.map(|p| p.line).unwrap_or_default();
+            (issue.severity, issue.register.as_deref(), line)
+        })
+        .collect();
+    assert_eq!(
+        issues,
+        [
+            (Severity::Error, Some("Alarm"), 10),
+            (Severity::Error, Some("Speed"), 11),
+            (Severity::Error, Some("Pump"), 12),
+            (Severity::Warning, Some("Level"), 13),
+            (Severity::Warning, Some("Level"), 13),
+            (Severity::Error, Some("Label"), 15),
+            (Severity::Error, None, 16),
+            (Severity::Warning, Some("Total"), 17),
+        ],
+        "{report}"
+    );
+    let column = report.issues[0].position.unwrap().column;
+    assert_eq!(column, 5);
+    let total = report.issues.last().unwrap();
+    assert!(
+        total
+            .message
+            .contains("only marks the start of the 4 bytes of a INT32"),
+        "{total}"
+    );
+}
+
+#[test]
+fn test_register_metadata() {
+    let file = File::open("tests/test_registers_metadata.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+
+    let pressure = &defs["Pressure"];
+    assert_eq!(
+        pressure.description.as_deref(),
+        Some("Pressure at the outlet of the pump")
+    );
+    assert_eq!(pressure.unit.as_deref(), Some("bar"));
+    assert_eq!(pressure.access, Access::ReadOnly);
+    assert_eq!((pressure.min, pressure.max), (Some(0.0), Some(16.0)));
+    assert_eq!(pressure.tags, ["pump", "process"]);
+    assert!(pressure.access.is_readable() && !pressure.access.is_writable());
+
+    assert_eq!(defs["Setpoint"].access, Access::ReadWrite);
+    assert_eq!(defs["Reset"].access, Access::WriteOnly);
+    assert!(defs["Reset"].tags.is_empty());
+
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 102);
+    let dev = S7Device::new(addr, defs);
+    let names: Vec<&str> = dev.browse().iter().map(|reg| reg.name.as_str()).collect();
+    assert_eq!(names, ["Pressure", "Reset", "Setpoint"]);
+    let names: Vec<&str> = dev
+        .browse_tag("pump")
+        .iter()
+        .map(|reg| reg.name.as_str())
+        .collect();
+    assert_eq!(names, ["Pressure", "Setpoint"]);
+}
+
+#[tokio::test]
+async fn test_write_protection() {
+    let file = File::open("tests/test_registers_metadata.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 102);
+    let mut dev = S7Device::new(addr, defs);
+
+    // the checks happen before the connection is needed
+    let res = dev
+        .write_register_by_name("Pressure", &RegisterValue::Float32(1.0))
+        .await;
+    assert!(matches!(res, Err(S7Error::ReadOnlyRegisterError { .. })));
+    let res = dev
+        .write_raw_register_by_name("Pressure", &RegisterValue::Float32(1.0))
+        .await;
+    assert!(matches!(res, Err(S7Error::ReadOnlyRegisterError { .. })));
+    // the access is checked before the limits (0 to 16 bar)
+    let res = dev
+        .write_register_by_name("Pressure", &RegisterValue::Float32(100.0))
+        .await;
+    assert!(matches!(res, Err(S7Error::ReadOnlyRegisterError { .. })));
+    let res = dev
+        .write_register_by_name("Setpoint", &RegisterValue::Float32(12.0))
+        .await;
+    assert!(matches!(res, Err(S7Error::OutOfLimitsError { value, .. }) if value == 12.0));
+    let res = dev
+        .write_register_by_name("Setpoint", &RegisterValue::Float32(5.0))
+        .await;
+    assert!(matches!(res, Err(S7Error::DeviceNotConnectedError)));
+
+    dev.set_limit_policy(LimitPolicy::Clamp);
+    let res = dev
+        .write_register_by_name("Setpoint", &RegisterValue::Float32(12.0))
+        .await;
+    assert!(matches!(res, Err(S7Error::DeviceNotConnectedError)));
+
+    let setpoint = dev.get_register_by_name("Setpoint").unwrap();
+    assert_eq!(
+        setpoint
+            .apply_limits(RegisterValue::Float32(-1.0), LimitPolicy::Clamp)
+            .unwrap(),
+        RegisterValue::Float32(0.0)
+    );
+}
+
+#[tokio::test]
+async fn test_read_protection() {
+    let file = File::open("tests/test_registers_metadata.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 102);
+    let mut dev = S7Device::new(addr, defs);
+
+    // the checks happen before the connection is needed
+    let res = dev.read_register_by_name("Reset").await;
+    assert!(matches!(res, Err(S7Error::WriteOnlyRegisterError { name }) if name == "Reset"));
+    let res = dev.read_raw_register_by_name("Reset").await;
+    assert!(matches!(res, Err(S7Error::WriteOnlyRegisterError { .. })));
+    // the bulk reads skip the write-only registers
+    assert!(dev.read_matching("Res*").await.unwrap().is_empty());
+    let reset = dev.get_register_by_name("Reset").unwrap().clone();
+    assert!(dev.read_registers(&[reset]).await.unwrap().is_empty());
+    let res = dev.dump_registers().await;
+    assert!(matches!(res, Err(S7Error::DeviceNotConnectedError)));
+}
+
+#[tokio::test]
+async fn test_register_groups() {
+    let file = File::open("tests/test_registers_groups.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+    assert_eq!(defs["Motor2Speed"].tags, ["line1_motors", "motors"]);
+    assert!(defs["Conveyor"].tags.is_empty());
+
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 102);
+    let mut dev = S7Device::new(addr, defs);
+    let names: Vec<&str> = dev
+        .browse_tag("motors")
+        .iter()
+        .map(|reg| reg.name.as_str())
+        .collect();
+    assert_eq!(names, ["Motor1Speed", "Motor2Speed", "Motor3Speed"]);
+
+    let res = dev.read_group("line2_motors").await;
+    assert!(matches!(res, Err(S7Error::GroupDoesNotExistsError { .. })));
+    let res = dev.read_group("line1_motors").await;
+    assert!(matches!(res, Err(S7Error::DeviceNotConnectedError)));
+
+    let vals = HashMap::from([("Motor3Speed".to_string(), RegisterValue::S16(10))]);
+    let res = dev.write_group("line1_motors", &vals).await;
+    assert!(matches!(res, Err(S7Error::RegisterDoesNotExistsError)));
+    let res = dev.write_group("motors", &vals).await;
+    assert!(matches!(res, Err(S7Error::DeviceNotConnectedError)));
+}
+
+#[tokio::test]
+async fn test_hierarchical_names() {
+    let file = File::open("tests/test_registers_hierarchy.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 102);
+    let mut dev = S7Device::new(addr, defs);
+
+    let find = |pattern| -> Vec<String> {
+        dev.find_registers(pattern)
+            .iter()
+            .map(|reg| reg.name.clone())
+            .collect()
+    };
+    assert_eq!(
+        find("Line1.*.Motor.*"),
+        [
+            "Line1.Station3.Motor.Current",
+            "Line1.Station3.Motor.Speed",
+            "Line1.Station4.Motor.Speed",
+        ]
+    );
+    assert_eq!(
+        find("**.Speed"),
+        [
+            "Line1.Motor.Speed",
+            "Line1.Station3.Motor.Speed",
+            "Line1.Station4.Motor.Speed",
+            "Line2.Station1.Motor.Speed",
+        ]
+    );
+    assert_eq!(find("Line1.Station3.**").len(), 3);
+    assert_eq!(find("Line*.Station?.Valve"), ["Line1.Station3.Valve"]);
+
+    assert_eq!(dev.browse_children(""), ["Line1", "Line2"]);
+    assert_eq!(
+        dev.browse_children("Line1"),
+        ["Motor", "Station3", "Station4"]
+    );
+    assert_eq!(
+        dev.browse_children("Line1.Station3.Valve"),
+        Vec::<String>::new()
+    );
+
+    let res = dev.read_matching("Line3.**").await.unwrap();
+    assert!(res.is_empty());
+    let res = dev.read_matching("Line2.**").await;
+    assert!(matches!(res, Err(S7Error::DeviceNotConnectedError)));
+}
+
+#[test]
+fn test_byte_order() {
+    let addr = ByteAddress {
+        area: MemoryArea::DataBlock,
+        db: 1,
+        byte: 0,
+    };
+    let cases = [
+        (ByteOrder::ABCD, vec![0x3F, 0xC0, 0x00, 0x00]),
+        (ByteOrder::DCBA, vec![0x00, 0x00, 0xC0, 0x3F]),
+        (ByteOrder::BADC, vec![0xC0, 0x3F, 0x00, 0x00]),
+        (ByteOrder::CDAB, vec![0x00, 0x00, 0x3F, 0xC0]),
+    ];
+    for (byte_order, raw) in cases {
+        let mut reg = Register::new("Test", DataType::FLOAT, addr.clone().into());
+        reg.byte_order = byte_order;
+        let val: RegisterValue = (raw.clone(), reg.clone()).try_into().unwrap();
+        assert_eq!(val, RegisterValue::Float32(1.5), "{byte_order:?}");
+        assert_eq!(reg.encode(val).unwrap(), raw, "{byte_order:?}");
+    }
+
+    let mut reg = Register::new("Test", DataType::LINT, addr.clone().into());
+    reg.byte_order = ByteOrder::CDAB;
+    let raw = vec![7, 8, 5, 6, 3, 4, 1, 2];
+    let val: RegisterValue = (raw, reg.clone()).try_into().unwrap();
+    assert_eq!(val, RegisterValue::S64(0x0102_0304_0506_0708));
+
+    let mut reg = Register::new("Test", DataType::INT16, addr.into());
+    reg.byte_order = ByteOrder::DCBA;
+    reg.array = Some((0, 1).into());
+    let val: RegisterValue = (vec![1, 0, 2, 0], reg.clone()).try_into().unwrap();
+    assert_eq!(
+        val,
+        RegisterValue::Array(vec![RegisterValue::S16(1), RegisterValue::S16(2)])
+    );
+    assert_eq!(reg.encode(val).unwrap(), [1, 0, 2, 0]);
+}
+
+#[test]
+fn test_scl_import() {
+    let file = File::open("tests/test_db.db").unwrap();
+    let defs = scl::get_defs_from_scl(file, &HashMap::from([("Line".to_string(), 5)])).unwrap();
+    assert_eq!(defs.len(), 11);
+    let addr = |name: &str| defs[name].addr.to_string();
+    assert_eq!(addr("Line.Enable"), "DB5.DBX0.0");
+    assert_eq!(addr("Line.Reset"), "DB5.DBX0.1");
+    assert_eq!(addr("Line.Mode"), "DB5.DBB1");
+    assert_eq!(addr("Line.Count"), "DB5.DBB2");
+    assert_eq!(addr("Line.Temperatures"), "DB5.DBB6");
+    assert_eq!(addr("Line.Flags"), "DB5.DBX18.0");
+    assert_eq!(addr("Line.Label"), "DB5.DBB20");
+    assert_eq!(addr("Line.Status"), "DB5.DBB32");
+    assert_eq!(addr("Line.Pump"), "DB5.DBB34");
+    assert_eq!(addr("Line.Motors"), "DB5.DBB40");
+    assert_eq!(addr("Line.Total"), "DB5.DBB48");
+
+    assert_eq!(
+        defs["Line.Enable"].description.as_deref(),
+        Some("Line enabled")
+    );
+    assert_eq!(defs["Line.Temperatures"].array, Some((1, 3).into()));
+    assert_eq!(defs["Line.Label"].length, Some(10));
+    assert_eq!(defs["Line.Pump"].size(), 6);
+    let valve = defs["Line.Pump"].field("Valve").unwrap();
+    assert_eq!(valve.addr.to_string(), "DB5.DBX38.0");
+    let motors = &defs["Line.Motors"];
+    assert_eq!(motors.size(), 8);
+    let fault = motors.element(2).unwrap().field("Fault").unwrap();
+    assert_eq!(fault.addr.to_string(), "DB5.DBX46.1");
+    assert_eq!(fault.data_type, DataType::BOOL);
+    assert_eq!(defs["Line.Count"].initial, Some(RegisterValue::S32(5)));
+    assert_eq!(
+        defs["Line.Total"].initial,
+        Some(RegisterValue::Float64(0.0))
+    );
+    assert_eq!(defs["Line.Mode"].initial, None);
+
+    let source = "DATA_BLOCK DB7 STRUCT a : Int; b : Array[0..1, 0..2] of Byte; END_STRUCT; BEGIN END_DATA_BLOCK";
+    assert!(matches!(
+        scl::get_defs_from_scl_str(source, &HashMap::new()),
+        Err(SourceError::MultiDimArray { line: 1 })
+    ));
+
+    let optimized = "DATA_BLOCK DB1 { S7_Optimized_Access := 'TRUE' } STRUCT a : Int; END_STRUCT; BEGIN END_DATA_BLOCK";
+    assert!(matches!(
+        scl::get_defs_from_scl_str(optimized, &HashMap::new()),
+        Err(SourceError::OptimizedBlock { .. })
+    ));
+    let unknown =
+        "DATA_BLOCK DB1 STRUCT\n a : Int;\n b : \"Missing\";\nEND_STRUCT; BEGIN END_DATA_BLOCK";
+    assert!(matches!(
+        scl::get_defs_from_scl_str(unknown, &HashMap::new()),
+        Err(SourceError::UnknownType { line: 3, .. })
+    ));
+    let unnumbered = "DATA_BLOCK \"Data\" STRUCT a : Int; END_STRUCT; BEGIN END_DATA_BLOCK";
+    assert!(matches!(
+        scl::get_defs_from_scl_str(unnumbered, &HashMap::new()),
+        Err(SourceError::UnknownDbNumber { .. })
+    ));
+    let too_long = "DATA_BLOCK DB1 STRUCT\n a : String[255];\nEND_STRUCT; BEGIN END_DATA_BLOCK";
+    assert!(matches!(
+        scl::get_defs_from_scl_str(too_long, &HashMap::new()),
+        Err(SourceError::Syntax { line: 2, .. })
+    ));
+}
+
+#[test]
+fn test_symbol_tables() {
+    for file in [
+        "tests/test_symbols.sdf",
+        "tests/test_symbols.asc",
+        "tests/test_symbols.seq",
+    ] {
+        let extension = file.rsplit('.').next().unwrap();
+        let format = utils::SymbolTableFormat::from_extension(extension).unwrap();
+        let defs = utils::get_defs_from_symbols(File::open(file).unwrap(), format).unwrap();
+        assert_eq!(defs.len(), 5, "{file}");
+        let start = &defs["Start"];
+        assert_eq!(start.data_type, DataType::BOOL, "{file}");
+        assert_eq!(start.addr.to_string(), "I0.0", "{file}");
+        assert_eq!(start.description.as_deref(), Some("Start button"), "{file}");
+        assert_eq!(defs["Setpoint"].data_type, DataType::INT16, "{file}");
+        assert_eq!(defs["Setpoint"].addr.to_string(), "MB10", "{file}");
+        assert_eq!(defs["Flow"].data_type, DataType::FLOAT, "{file}");
+        assert_eq!(defs["Flow"].description, None, "{file}");
+        assert_eq!(defs["Temp_Raw"].data_type, DataType::WORD, "{file}");
+        assert_eq!(defs["Temp_Raw"].addr.to_string(), "IB256", "{file}");
+        assert_eq!(defs["Valve"].addr.to_string(), "Q4.1", "{file}");
+        assert!(!defs.contains_key("Main") && !defs.contains_key("Recipe"));
+    }
+    let defs = utils::get_defs_from_symbols(
+        File::open("tests/test_symbols.seq").unwrap(),
+        utils::SymbolTableFormat::Seq,
+    )
+    .unwrap();
+    assert_eq!(
+        defs["Valve"].description.as_deref(),
+        Some("Ventil geöffnet")
+    );
+    // the bytes 0x80 to 0x9F are not control characters in Windows-1252
+    let path = std::env::temp_dir().join("s7_device_symbols_cp1252.seq");
+    std::fs::write(
+        &path,
+        b"=\tMW 20\tINT\tCost\tPreis in \x80, \x84netto\x93\r\n",
+    )
+    .unwrap();
+    let defs =
+        utils::get_defs_from_symbols(File::open(&path).unwrap(), utils::SymbolTableFormat::Seq)
+            .unwrap();
+    assert_eq!(
+        defs["Cost"].description.as_deref(),
+        Some("Preis in €, „netto“")
+    );
+    let path = std::env::temp_dir().join("s7_device_symbols_duplicate.seq");
+    std::fs::write(&path, "=\tMW 20\tINT\tCost\t\r\n=\tMW 22\tINT\tCost\t\r\n").unwrap();
+    assert!(matches!(
+        utils::get_defs_from_symbols(File::open(&path).unwrap(), utils::SymbolTableFormat::Seq),
+        Err(utils::SymbolTableError::DuplicateRegister { line: 2, name }) if name == "Cost"
+    ));
+}
+
+#[test]
+fn test_awl_import() {
+    let file = File::open("tests/test_db.awl").unwrap();
+    let defs = awl::get_defs_from_awl(file, &HashMap::new()).unwrap();
+    assert_eq!(defs.len(), 10);
+    let addr = |name: &str| defs[name].addr.to_string();
+    assert_eq!(addr("DB10.Active"), "DB10.DBX0.0");
+    assert_eq!(addr("DB10.Batch"), "DB10.DBB2");
+    assert_eq!(addr("DB10.Name"), "DB10.DBB10");
+    assert_eq!(addr("DB10.Steps"), "DB10.DBB26");
+    assert_eq!(addr("DB10.Flags"), "DB10.DBX34.0");
+    assert_eq!(addr("DB10.Drive"), "DB10.DBB36");
+    assert_eq!(addr("DB10.Spare"), "DB10.DBB40");
+    assert_eq!(
+        defs["DB10.Active"].description.as_deref(),
+        Some("Rezept aktiv (Überwachung)")
+    );
+
+    let initial = |name: &str| defs[name].initial.clone();
+    assert_eq!(initial("DB10.Active"), Some(RegisterValue::Boolean(true)));
+    assert_eq!(initial("DB10.Batch"), Some(RegisterValue::S32(42)));
+    assert_eq!(initial("DB10.Ratio"), Some(RegisterValue::Float32(1.5)));
+    assert_eq!(
+        initial("DB10.Name"),
+        Some(RegisterValue::String("Mix'A".to_string()))
+    );
+    assert_eq!(initial("DB10.Mask"), Some(RegisterValue::U16(0xFF0F)));
+    assert_eq!(
+        initial("DB10.Delay"),
+        Some(RegisterValue::Duration(TimeDelta::milliseconds(1500)))
+    );
+    // the `BEGIN` section overwrites the declared values
+    assert_eq!(
+        initial("DB10.Steps"),
+        Some(RegisterValue::Array(
+            [10, 20, 20, 5].map(RegisterValue::S16).to_vec()
+        ))
+    );
+    let flags = &defs["DB10.Flags"];
+    assert_eq!(
+        flags.element(3).unwrap().initial,
+        Some(RegisterValue::Boolean(true))
+    );
+    assert_eq!(
+        flags.element(4).unwrap().initial,
+        Some(RegisterValue::Boolean(false))
+    );
+    let drive = &defs["DB10.Drive"];
+    assert_eq!(
+        drive.field("Speed").unwrap().initial,
+        Some(RegisterValue::S16(1200))
+    );
+    assert_eq!(initial("DB10.Spare"), None);
+
+    let source = "DATA_BLOCK DB 1 STRUCT a : INT := 'x'; END_STRUCT; BEGIN END_DATA_BLOCK";
+    assert!(matches!(
+        awl::get_defs_from_awl_str(source, &HashMap::new()),
+        Err(SourceError::InvalidValue { line: 1, .. })
+    ));
+    let source = "DATA_BLOCK DB 1 STRUCT a : INT; END_STRUCT; BEGIN b := 1; END_DATA_BLOCK";
+    assert!(matches!(
+        awl::get_defs_from_awl_str(source, &HashMap::new()),
+        Err(SourceError::UnknownMember { .. })
+    ));
+    // `DB 1` and `DB1` are the same block
+    let source = "DATA_BLOCK DB 1 STRUCT a : INT; END_STRUCT; BEGIN END_DATA_BLOCK\nDATA_BLOCK DB1 STRUCT a : INT; END_STRUCT; BEGIN END_DATA_BLOCK";
+    assert!(matches!(
+        awl::get_defs_from_awl_str(source, &HashMap::new()),
+        Err(SourceError::DuplicateRegister { name }) if name == "DB1.a"
+    ));
+}
+
+#[test]
+fn test_tag_table_import() {
+    let file = File::open("tests/test_tags.xlsx").unwrap();
+    let defs = xlsx::get_defs_from_tag_table(file).unwrap();
+    assert_eq!(defs.len(), 5);
+    let start = &defs["Start_Button"];
+    assert_eq!(start.data_type, DataType::BOOL);
+    assert_eq!(start.addr.to_string(), "I0.0");
+    assert_eq!(start.description.as_deref(), Some("Start button"));
+    assert_eq!(defs["Motor_On"].addr.to_string(), "Q4.1");
+    assert_eq!(defs["Motor_On"].description, None);
+    assert_eq!(defs["Setpoint"].data_type, DataType::INT16);
+    assert_eq!(defs["Setpoint"].addr.to_string(), "MB10");
+    assert_eq!(defs["Flow"].data_type, DataType::FLOAT);
+    assert_eq!(defs["Temp_Raw"].addr.to_string(), "IB64");
+    assert!(!defs.contains_key("Cycle_Timer") && !defs.contains_key("MAX_SPEED"));
+
+    // the last row repeats the first tag
+    let file = File::open("tests/test_tags_duplicate.xlsx").unwrap();
+    assert!(matches!(
+        xlsx::get_defs_from_tag_table(file),
+        Err(xlsx::TagTableError::Tag {
+            err: utils::SymbolTableError::DuplicateRegister { line: 8, name }
+        }) if name == "Start_Button"
+    ));
+}
+
+#[test]
+fn test_definition_formats() {
+    assert_eq!(
+        DefinitionFormat::from_extension("YML"),
+        Some(DefinitionFormat::Yaml)
+    );
+    assert_eq!(DefinitionFormat::from_extension("txt"), None);
+    let defs = formats::read_defs_from_path("tests/test_registers.csv").unwrap();
+    assert_eq!(defs.len(), 5);
+    let level = &defs["Level"];
+    assert_eq!(
+        level.scaling,
+        Some(Scaling::Range {
+            raw: (0.0, 27648.0),
+            eng: (0.0, 100.0)
+        })
+    );
+    assert_eq!(level.access, Access::ReadOnly);
+    assert_eq!(level.tags, vec!["process", "tank"]);
+    assert_eq!(
+        defs["Temperature"].scaling,
+        Some(Scaling::Linear {
+            factor: 0.1,
+            offset: -50.0
+        })
+    );
+    assert_eq!(defs["Speeds"].array, Some((1, 4).into()));
+    assert_eq!(defs["Recipe"].length, Some(20));
+    assert_eq!(defs["Counter"].byte_order, ByteOrder::DCBA);
+
+    let formats = [
+        DefinitionFormat::Json,
+        DefinitionFormat::Csv,
+        DefinitionFormat::Yaml,
+        DefinitionFormat::Toml,
+    ];
+    for path in [
+        "tests/test_registers.csv",
+        "tests/test_registers_metadata.json",
+        "tests/test_registers_arrays.json",
+        "tests/test_registers_structs.json",
+    ] {
+        let defs = formats::read_defs_from_path(path).unwrap();
+        for format in formats {
+            let mut out = Vec::new();
+            match formats::write_defs(&mut out, &defs, format) {
+                Err(FormatError::Unsupported { .. }) if format == DefinitionFormat::Csv => continue,
+                res => res.unwrap(),
+            }
+            let back = formats::read_defs(out.as_slice(), format).unwrap();
+            assert_eq!(back, defs, "{path} in {format:?}");
+        }
+    }
+
+    // the short strings take 2 or 4 bytes but are still addressed by their first byte
+    let mut defs = HashMap::new();
+    for (name, data_type, length, byte) in [
+        ("Empty", DataType::STRING, 0, 0),
+        ("Code", DataType::STRING, 2, 2),
+        ("Wide", DataType::WSTRING, 0, 6),
+    ] {
+        let addr = RegAddress::Byte(ByteAddress {
+            area: MemoryArea::DataBlock,
+            db: 1,
+            byte,
+        });
+        let mut reg = Register::new(name, data_type, addr);
+        reg.length = Some(length);
+        defs.insert(name.to_string(), reg);
+    }
+    for format in formats {
+        let mut out = Vec::new();
+        formats::write_defs(&mut out, &defs, format).unwrap();
+        let back = formats::read_defs(out.as_slice(), format).unwrap();
+        assert_eq!(back, defs, "{format:?}");
+    }
+    let mut out = Vec::new();
+    formats::write_defs(&mut out, &defs, DefinitionFormat::Json).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("DB1.DBB2") && !text.contains("DBD") && !text.contains("DBW"));
+}
+
+#[test]
+fn test_definitions_schema() {
+    let schema = schema::definitions_schema();
+    let published: serde_json::Value =
+        serde_json::from_reader(File::open("registers.schema.json").unwrap()).unwrap();
+    assert_eq!(published, schema, "registers.schema.json is out of date");
+    assert_eq!(
+        published["$defs"]["dataType"]["enum"],
+        serde_json::json!(DataType::names().collect::<Vec<_>>())
+    );
+    for data_type in DataType::ALL {
+        let name = serde_json::to_value(data_type).unwrap();
+        assert_eq!(name, data_type.name());
+        assert_eq!(serde_json::from_value::<DataType>(name).unwrap(), data_type);
+        for alias in data_type.aliases() {
+            assert_eq!(
+                serde_json::from_value::<DataType>(serde_json::Value::from(*alias)).unwrap(),
+                data_type
+            );
+        }
+    }
+    assert!(serde_json::from_value::<DataType>(serde_json::Value::from("INTEGER")).is_err());
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    for path in [
+        "tests/test_registers.json",
+        "tests/test_registers_areas.json",
+        "tests/test_registers_arrays.json",
+        "tests/test_registers_groups.json",
+        "tests/test_registers_hierarchy.json",
+        "tests/test_registers_metadata.json",
+        "tests/test_registers_scaling.json",
+        "tests/test_registers_structs.json",
+        "tests/test_registers_templates.json",
+    ] {
+        let file: serde_json::Value = serde_json::from_reader(File::open(path).unwrap()).unwrap();
+        assert!(validator.is_valid(&file), "{path}");
+    }
+    for invalid in [
+        serde_json::json!([{ "name": "A", "id": "DB1.DBW0" }]),
+        serde_json::json!([{ "name": "A", "id": "DB1.DBW0", "type": "INTEGER" }]),
+        serde_json::json!([{ "name": "A", "id": "DB1.DBW0", "type": "INT", "array": [0] }]),
+        serde_json::json!({ "types": { "T": [{ "name": "F", "type": "BOOL", "offset": 0, "bit": 8 }] }, "registers": [] }),
+        serde_json::json!({ "groups": {} }),
+    ] {
+        assert!(!validator.is_valid(&invalid), "{invalid}");
+    }
+
+    // the exported files are read back to the same registers
+    let defs = utils::get_defs_from_json(File::open("tests/test_registers_structs.json").unwrap())
+        .unwrap();
+    let mut json = Vec::new();
+    formats::write_defs(&mut json, &defs, DefinitionFormat::Json).unwrap();
+    assert!(validator.is_valid(&serde_json::from_slice(&json).unwrap()));
+    let path = std::env::temp_dir().join("s7_device_export.json");
+    std::fs::write(&path, &json).unwrap();
+    assert_eq!(
+        utils::get_defs_from_json(File::open(&path).unwrap()).unwrap(),
+        defs
+    );
+}
+
+#[test]
+fn test_manifest() {
+    let defs = manifest::get_defs_from_manifest("tests/test_manifest.json").unwrap();
+    assert_eq!(defs.len(), 11);
+    assert_eq!(defs["Station1.Pressure"].addr.to_string(), "DB7.DBB0");
+    assert_eq!(defs["Station2.Pressure"].addr.to_string(), "DB17.DBB0");
+    assert_eq!(defs["Station2.Reset"].addr.to_string(), "DB17.DBX8.0");
+    assert_eq!(defs["Station2.Pressure"].unit.as_deref(), Some("bar"));
+    assert!(defs.contains_key("Level"));
+
+    let res = manifest::get_defs_from_manifest("tests/test_manifest_collision.json");
+    assert!(matches!(
+        res,
+        Err(ManifestError::Collision { name, first, second })
+            if name == "Counter" && first == "test_registers_scaling.json" && second == "test_registers.csv"
+    ));
+
+    // a name defined twice by the same file is a collision too
+    let res = manifest::get_defs_from_manifest("tests/test_manifest_duplicate.json");
+    assert!(matches!(
+        res,
+        Err(ManifestError::Include {
+            path,
+            err: FormatError::Definitions { err: utils::JsonReadError::DuplicateRegister { name } },
+        }) if path == "test_registers_duplicate.csv" && name == "Level"
+    ));
+}
+
+#[tokio::test]
+async fn test_peripheral_areas() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 102);
+    let mut dev = S7Device::new(addr, HashMap::new());
+    let reg = Register::new(
+        "Analog",
+        DataType::INT16,
+        "PIW256".parse::<RegAddress>().unwrap(),
+    );
+    assert!(matches!(
+        dev.read_register(&reg).await,
+        Err(S7Error::UnsupportedAreaError { .. })
+    ));
+}
+
+#[test]
+fn test_hot_reload() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 102);
+    let defs = utils::get_defs_from_json(File::open("tests/test_registers.json").unwrap()).unwrap();
+    let count = defs.len();
+    let mut dev = S7Device::new(addr, defs);
+    let reg = Register::new(
+        "Extra",
+        DataType::INT16,
+        "DB1.DBW100".parse::<ByteAddress>().unwrap().into(),
+    );
+    dev.add_register(reg.clone()).unwrap();
+    assert!(matches!(
+        dev.add_register(reg.clone()),
+        Err(S7Error::RegisterAlreadyExistsError { .. })
+    ));
+    let replaced = Register {
+        unit: Some("rpm".to_string()),
+        ..reg.clone()
+    };
+    assert_eq!(dev.replace_register(replaced).unwrap(), reg);
+    assert_eq!(
+        dev.get_register_by_name("Extra").unwrap().unit.as_deref(),
+        Some("rpm")
+    );
+    assert_eq!(dev.remove_register("Extra").unwrap().name, "Extra");
+    assert!(dev.remove_register("Extra").is_err());
+    assert_eq!(dev.browse().len(), count);
+
+    let path = std::env::temp_dir().join("s7_device_reload.json");
+    let write = |text: &str, secs: u64| {
+        std::fs::write(&path, text).unwrap();
+        let mtime = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    };
+    write(
+        r#"[{ "name": "Speed", "id": "DB2.DBW0", "type": "INT" }]"#,
+        1000,
+    );
+    let mut poller = DefinitionsPoller::new(&path).unwrap();
+    assert!(dev.reload(&mut poller).unwrap());
+    assert!(!dev.reload(&mut poller).unwrap());
+    assert_eq!(dev.browse().len(), 1);
+    // invalid definitions are rejected and the registers are kept
+    write(
+        r#"[{ "name": "Speed", "id": "DB2.DBW0", "type": "DINT" }]"#,
+        2000,
+    );
+    assert!(matches!(
+        dev.reload(&mut poller),
+        Err(ReloadError::Invalid { .. })
+    ));
+    assert_eq!(
+        dev.get_register_by_name("Speed").unwrap().data_type,
+        DataType::INT16
+    );
+    // the failed change is not consumed, it is loaded again until it succeeds
+    assert!(matches!(
+        dev.reload(&mut poller),
+        Err(ReloadError::Invalid { .. })
+    ));
+    write(
+        r#"[{ "name": "Speed", "id": "DB2.DBD0", "type": "DINT" }]"#,
+        3000,
+    );
+    assert!(dev.reload(&mut poller).unwrap());
+    assert_eq!(
+        dev.get_register_by_name("Speed").unwrap().data_type,
+        DataType::INT32
+    );
+    // the content is compared, a change keeping the size and the modification time is seen
+    write(
+        r#"[{ "name": "Speed", "id": "DB2.DBD4", "type": "DINT" }]"#,
+        3000,
+    );
+    assert!(dev.reload(&mut poller).unwrap());
+    assert_eq!(dev.get_register_by_name("Speed").unwrap().byte_offset(), 4);
+    // the peripheral I/O cannot be accessed by the connection
+    write(
+        r#"[{ "name": "Speed", "id": "PIW256", "type": "INT" }]"#,
+        4000,
+    );
+    assert!(dev.reload(&mut poller).is_err());
+    assert_eq!(dev.get_register_by_name("Speed").unwrap().byte_offset(), 4);
+}
+
+#[test]
+fn test_templates() {
+    let file = File::open("tests/test_registers_templates.json").unwrap();
+    let defs = utils::get_defs_from_json(file).unwrap();
+    assert_eq!(defs.len(), 13);
+    assert_eq!(defs["Line.Valve1.Open"].addr.to_string(), "DB101.DBX0.0");
+    assert_eq!(defs["Line.Valve2.Fault"].addr.to_string(), "DB102.DBX0.1");
+    assert_eq!(defs["Line.Valve2.Fault"].access, Access::ReadOnly);
+    assert_eq!(defs["Line.Valve2.Fault"].tags, vec!["faults"]);
+    let position = &defs["Line.Valve3.Position"];
+    assert_eq!(position.addr.to_string(), "DB100.DBB22");
+    assert_eq!(
+        (position.unit.as_deref(), position.max),
+        (Some("%"), Some(100.0))
+    );
+    assert_eq!(defs["Line.Valve3.Name"].length, Some(10));
+
+    // a generated register does not replace a listed one
+    let text = std::fs::read_to_string("tests/test_registers_templates.json").unwrap();
+    let clashing = text.replace(
+        r#""name": "Line.Pressure""#,
+        r#""name": "Line.Valve1.Open""#,
+    );
+    let res = formats::read_defs(clashing.as_bytes(), DefinitionFormat::Json);
+    assert!(matches!(
+        res,
+        Err(FormatError::Definitions { err: utils::JsonReadError::DuplicateRegister { name } })
+            if name == "Line.Valve1.Open"
+    ));
+
+    let text = std::fs::read_to_string("tests/test_registers_templates.json").unwrap();
+    let report = validation::validate_str(&text);
+    assert!(report.is_valid(), "{report}");
+    let overlapping = text.replace(r#""offset": 20"#, r#""offset": 2"#);
+    let report = validation::validate_str(&overlapping);
+    assert!(report
+        .warnings()
+        .any(|issue| issue.register.as_deref() == Some("Line.Valve3.Open")));
+    let unknown = text.replace(
+        r#""template": "Valve", "prefix": "Line.Valve3""#,
+        r#""template": "Pump", "prefix": "Line.Valve3""#,
+    );
+    assert!(!validation::validate_str(&unknown).is_valid());
+}
+
+#[test]
+fn test_layout() {
+    let mut name = member("Name", DataType::STRING);
+    name.length = Some(4);
+    let regs = layout::layout_block(
+        5,
+        vec![
+            member("Running", DataType::BOOL),
+            member("Fault", DataType::BOOL),
+            member("Speed", DataType::INT16),
+            member("Current", DataType::FLOAT),
+            member("Mode", DataType::BYTE),
+            array_member("Flags", DataType::BOOL, (0, 2)),
+            struct_member(
+                "Valve",
+                vec![
+                    member("Open", DataType::BOOL),
+                    member("Position", DataType::INT16),
+                ],
+            ),
+            member("Step", DataType::BYTE),
+            name,
+        ],
+    )
+    .unwrap();
+    let addrs: Vec<String> = regs.iter().map(|reg| reg.addr.to_string()).collect();
+    assert_eq!(
+        addrs,
+        [
+            "DB5.DBX0.0",
+            "DB5.DBX0.1",
+            "DB5.DBB2",
+            "DB5.DBB4",
+            "DB5.DBB8",
+            "DB5.DBX10.0",
+            "DB5.DBB12",
+            "DB5.DBB16",
+            "DB5.DBB18"
+        ]
+    );
+    assert_eq!(
+        regs[6].field("Position").unwrap().addr.to_string(),
+        "DB5.DBB14"
+    );
+
+    let map = layout::memory_map(&regs).unwrap();
+    assert_eq!(map.size, 24);
+    assert_eq!(map.entries.len(), 11);
+    let position = &map.entries[8];
+    assert_eq!(
+        (position.name.as_str(), position.depth),
+        ("Valve.Position", 1)
+    );
+    assert_eq!(
+        (position.address.as_str(), position.bits),
+        ("DB5.DBW14", 16)
+    );
+    assert_eq!(map.entries[5].data_type, "ARRAY[0..2] OF BOOL");
+    assert_eq!(map.entries[10].data_type, "STRING[4]");
+    let text = map.to_string();
+    assert!(
+        text.contains("DB5.DBX0.1       0.1      0.1  Fault"),
+        "{text}"
+    );
+    assert!(text.ends_with("Size : 24 bytes"));
+
+    // a data block is limited to 65535 bytes
+    let err = layout::layout_block(
+        5,
+        vec![
+            array_member("Samples", DataType::LREAL, (0, 8190)),
+            member("Last", DataType::LREAL),
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The member Last ends past the 65535 bytes of a data block"
+    );
+    let big = array_member("Big", DataType::BYTE, (0, 69999));
+    assert_eq!(big.checked_size(), None);
+    assert!(layout::layout_block(5, vec![big]).is_err());
+}
+
 async fn create_dev(server: &ContainerAsync<GenericImage>) -> S7Device {
     let port = server.get_host_port_ipv4(102_u16).await.unwrap();
 